@@ -1,10 +1,28 @@
-use std::sync::Arc;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    ops::RangeInclusive,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use ratatui::crossterm::event::KeyCode;
+use chrono::{DateTime, Utc};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use smart_default::SmartDefault;
 use tokio::{sync::RwLock, task::JoinHandle};
 
-use crate::{keybindings::default_keybindings, max_sliding_window::MaxSlidingWindow};
+use crate::{
+    config::{Config, ContainerSort, CrashAlert, Metric},
+    diagnostics::Diagnostics,
+    docker::{ContainerAction, ContainerInfo, ResourceLimits, UsageSnapshot},
+    keybindings::{
+        MenuItem, activate_menu_item, default_keybindings, menu_items, queue_custom_action,
+    },
+    log_throughput::LogThroughput,
+    max_sliding_window::MaxSlidingWindow,
+    search,
+    state::SavedState,
+    text_input::TextInput,
+};
 
 #[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
 pub enum AppMode {
@@ -15,85 +33,884 @@ pub enum AppMode {
     Search,
     Help,
     Resources,
+    Details,
+    Labels,
+    Events,
+    Diagnostics,
+    Rename,
+    ConfirmRemove,
+    ConfirmAction,
+    QuickOpen,
 }
 
+/// A log stream kept running after its overlay closed, so reopening it is instant.
+pub struct ParkedLogs {
+    pub logs: Vec<String>,
+    pub truncated: bool,
+    pub last_log_time: Option<DateTime<Utc>>,
+    pub task: JoinHandle<()>,
+}
+
+/// Resource graphs of one container captured to compare the live ones against.
+pub struct StatsBaseline {
+    pub container_id: String,
+    pub cpu: MaxSlidingWindow<f64>,
+    pub mem: MaxSlidingWindow<f64>,
+}
+
+impl AppMode {
+    /// Modes drawn in the resizable overlay.
+    pub fn has_overlay(self) -> bool {
+        matches!(
+            self,
+            AppMode::Logs
+                | AppMode::Resources
+                | AppMode::Details
+                | AppMode::Labels
+                | AppMode::Events
+                | AppMode::Diagnostics
+        )
+    }
+}
+
+/// Placeholder shown in the logs overlay until the first lines arrive.
+pub const LOADING_LOGS: &str = "Loading logs...";
+
+/// Trailing lines remembered when closing logs to find them again on the next open.
+const SEEN_FINGERPRINT_LINES: usize = 3;
+
+/// Number of recent Docker events kept for the events overlay.
+pub const MAX_EVENTS: usize = 100;
+
 #[derive(SmartDefault)]
 pub struct AppState {
     #[default = true]
     pub running: bool,
-    pub container_data: Vec<(String, Vec<String>)>,
+    pub config: Config,
+    pub container_data: Vec<ContainerInfo>,
+    pub label_filter: Option<String>,
+    #[default = false]
+    pub paused: bool,
     pub selected: usize,
     pub mode: AppMode,
     pub last_mode: AppMode,
     pub menu_selected: usize,
     pub logs: Vec<String>,
-    #[default(_code = "vec![\"Logs\", \"Stats\", \"Restart\"]")]
-    pub menu_items: Vec<&'static str>,
+    #[default(_code = "menu_items()")]
+    pub menu_items: Vec<MenuItem>,
     pub horizontal_scroll: u16,
     pub vertical_scroll: u16,
     pub log_task: Option<JoinHandle<()>>,
+    /// Container whose stream `log_task` is and whose lines `logs` holds.
+    pub logs_for: Option<String>,
+    /// Streams of recently closed logs, least recently viewed first.
+    pub parked_logs: VecDeque<(String, ParkedLogs)>,
+    /// Last lines seen per container, recorded when its logs overlay closes.
+    pub seen_logs: HashMap<String, Vec<String>>,
+    /// Index of the first log line that arrived since the previous visit.
+    pub unread_from: Option<usize>,
+    /// Set when the logs overlay opens, cleared once the stream delivers lines or ends.
+    #[default = false]
+    pub logs_loading: bool,
+    /// Time of the newest line already shown, that a reconnected log stream
+    /// resumes after, keeping the buffer.
+    pub log_since: Option<DateTime<Utc>>,
+    /// Docker's timestamp of the newest line received, or when the stream
+    /// ended if none arrived, for `r` to resume from.
+    pub last_log_time: Option<DateTime<Utc>>,
+    pub log_throughput: LogThroughput,
+    /// Resource graphs shown beside the logs, with both streams running.
+    #[default = false]
+    pub split_view: bool,
+    /// Whether `config.log_filter` applies to incoming lines.
+    #[default = true]
+    pub log_filter_enabled: bool,
+    /// Lines the log filter dropped since the logs overlay opened.
+    pub hidden_log_lines: usize,
+    /// Set once `logs` dropped its oldest lines to stay under the line limit.
+    pub logs_truncated: bool,
+    /// Last error from the log stream, kept out of `logs` so it isn't mistaken for output.
+    pub log_error: Option<String>,
     #[default = false]
     pub user_scrolled: bool,
+    /// Number of trailing log lines added by the most recent flush, and when.
+    pub last_log_flush: Option<(usize, Instant)>,
+    #[default = false]
+    pub collapse_duplicates: bool,
+    #[default = false]
+    pub structured_logs: bool,
+    #[default = false]
+    pub ansi_colors: bool,
+    /// Wrap long log lines instead of scrolling sideways with `h`/`l`.
+    #[default = false]
+    pub wrap_logs: bool,
+    /// Show log lines past `config.long_line_screens` in full.
+    #[default = false]
+    pub expand_long_lines: bool,
     pub visible_height: u16,
-    pub search_query: String,
+    pub search_query: TextInput,
+    #[default = true]
+    pub fuzzy_search: bool,
+    pub table_filter: Option<String>,
     pub search_matches: Vec<usize>,
     pub current_match_index: Option<usize>,
     pub cpu_data: MaxSlidingWindow<f64>,
     pub mem_data: MaxSlidingWindow<f64>,
     pub stats_task: Option<JoinHandle<()>>,
+    pub stats_message: Option<String>,
+    pub resource_limits: Option<ResourceLimits>,
+    /// Launched with `--stats`: the resources view fills the screen and
+    /// closing it quits.
+    pub stats_only: bool,
+    /// Graphs captured with `B`, drawn dimmed behind the live ones.
+    pub stats_baseline: Option<StatsBaseline>,
+    /// Cores the stats stream reports; CPU % can reach 100 per core.
+    pub online_cpus: Option<u64>,
+    /// Metric expanded to the whole Resources overlay; `None` shows all configured ones.
+    pub focused_metric: Option<Metric>,
+    #[default = false]
+    pub show_memory: bool,
+    #[default = false]
+    pub show_cpu: bool,
+    #[default = false]
+    pub show_preview: bool,
+    /// Host summary strip above the container table.
+    #[default = false]
+    pub show_summary: bool,
+    /// Container whose log preview is loaded (or being fetched).
+    pub preview_for: Option<String>,
+    pub preview_logs: Vec<String>,
+    pub preview_task: Option<JoinHandle<()>>,
+    /// Container whose usage the open context menu shows (or is sampling).
+    pub menu_usage_for: Option<String>,
+    pub menu_usage: Option<UsageSnapshot>,
+    pub menu_usage_task: Option<JoinHandle<()>>,
+    pub cpu_history: HashMap<String, MaxSlidingWindow<f64>>,
+    #[default = false]
+    pub open_pager: bool,
+    #[default = false]
+    pub export_logs: bool,
+    pub rename_input: TextInput,
+    pub quick_open_input: TextInput,
+    /// Selection to restore when quick-open is cancelled.
+    pub quick_open_from: usize,
+    pub pending_rename: Option<(String, String)>,
+    pub confirm_input: TextInput,
+    pub pending_remove: Option<String>,
+    /// The stop or restart waiting for its timeout to be confirmed.
+    pub confirm_action: Option<ContainerAction>,
+    /// Ids of the containers `confirm_action` applies to.
+    pub action_targets: Vec<String>,
+    pub timeout_input: TextInput,
+    /// Confirmed action, container ids and timeout in seconds.
+    pub pending_action: Option<(ContainerAction, Vec<String>, u32)>,
+    /// Shell command or log lines waiting to be copied to the clipboard.
+    pub pending_copy: Option<String>,
+    /// Visual selection in the logs started with `v`: the line it started on
+    /// and the line the cursor has moved to, as indices into `logs`.
+    pub log_selection: Option<(usize, usize)>,
+    /// Custom action command waiting to run, and whether to capture its output.
+    pub pending_command: Option<(String, bool)>,
+    pub notification: Option<String>,
+    /// Why the open prompt's input was refused, shown in its title. Kept apart
+    /// from `notification`, which background tasks may overwrite meanwhile.
+    pub prompt_error: Option<String>,
+    /// Containers kept at the top of the list, whatever its order.
+    pub pinned: HashSet<String>,
+    /// Containers to announce once they stop running.
+    pub watched: HashSet<String>,
+    /// Whether the terminal bell should ring before the next draw.
+    pub ring_bell: bool,
+    /// Desktop notification to send through the terminal before the next draw.
+    pub desktop_alert: Option<String>,
+    /// When each container last raised a crash alert, to debounce crash loops.
+    pub crash_alerted: HashMap<String, Instant>,
+    pub refresh_error: Option<String>,
+    /// When the container list was last fetched successfully.
+    pub last_refresh: Option<Instant>,
+    /// Recent Docker events as Unix time and description.
+    pub events: VecDeque<(i64, String)>,
+    /// Tugboat's own warnings and errors, shown with `!`.
+    pub diagnostics: Diagnostics,
 }
 
 pub type SharedState = Arc<RwLock<AppState>>;
 
+/// Pinned containers come first, each group in `sort` order. The sorts are
+/// stable, so containers created in the same second stay in name order, which
+/// is how `get_container_data` returns them.
+fn sort_containers(
+    container_data: &mut [ContainerInfo],
+    sort: ContainerSort,
+    pinned: &HashSet<String>,
+) {
+    match sort {
+        ContainerSort::Name => container_data.sort_by(|a, b| a.names.cmp(&b.names)),
+        ContainerSort::Newest => container_data.sort_by_key(|c| std::cmp::Reverse(c.created)),
+        ContainerSort::Oldest => container_data.sort_by_key(|c| c.created),
+    }
+    container_data.sort_by_key(|c| !pinned.contains(&c.id));
+}
+
+/// How long freshly flushed log lines stay highlighted.
+const FRESH_LOG_DURATION: Duration = Duration::from_secs(2);
+
 impl AppState {
-    pub fn handle_input(&mut self, key: KeyCode) {
-        for binding in default_keybindings() {
-            if self.mode == AppMode::Search {
-                let search_keys = [KeyCode::Backspace, KeyCode::Enter, KeyCode::Esc];
-                if !search_keys.contains(&key) {
-                    if let KeyCode::Char(c) = key {
-                        self.search_query.push(c);
-                        return;
-                    }
+    /// The container the selection currently points at, if the list isn't empty.
+    pub fn selected_container(&self) -> Option<&ContainerInfo> {
+        self.container_data
+            .get(self.selected)
+            .filter(|container| self.is_visible(container))
+    }
+
+    /// The query narrowing the container table: live while a search typed in
+    /// Normal mode is open, afterwards the committed filter.
+    pub fn container_filter(&self) -> Option<&str> {
+        if self.mode == AppMode::Search && self.last_mode == AppMode::Normal {
+            Some(self.search_query.value()).filter(|query| !query.is_empty())
+        } else {
+            self.table_filter.as_deref()
+        }
+    }
+
+    fn is_visible(&self, container: &ContainerInfo) -> bool {
+        self.container_filter()
+            .is_none_or(|query| container.filter_score(query, self.fuzzy_search).is_some())
+    }
+
+    /// Indices into `container_data` of the rows the table shows, best matches
+    /// first while a filter is active.
+    pub fn visible_containers(&self) -> Vec<usize> {
+        let Some(query) = self.container_filter() else {
+            return (0..self.container_data.len()).collect();
+        };
+        let mut scored: Vec<(usize, i64)> = self
+            .container_data
+            .iter()
+            .enumerate()
+            .filter_map(|(i, container)| {
+                Some((i, container.filter_score(query, self.fuzzy_search)?))
+            })
+            .collect();
+        scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+        scored.into_iter().map(|(i, _)| i).collect()
+    }
+
+    /// Moves the selection onto the first shown row when the filter hides it.
+    fn keep_selection_visible(&mut self) {
+        if self.selected_container().is_none()
+            && let Some(&first) = self.visible_containers().first()
+        {
+            self.selected = first;
+        }
+    }
+
+    /// Index of the container whose id starts with `query` or whose name is
+    /// `query`, as `docker` accepts either.
+    pub fn find_container(&self, query: &str) -> Option<usize> {
+        self.container_data.iter().position(|container| {
+            container.id.starts_with(query)
+                || container
+                    .names
+                    .split(", ")
+                    .any(|name| name.trim_start_matches('/') == query)
+        })
+    }
+
+    /// Selects the container with `id`, leaving the selection alone if it's gone.
+    pub fn select_container_id(&mut self, id: &str) {
+        if let Some(i) = self
+            .container_data
+            .iter()
+            .position(|container| container.id == id)
+        {
+            self.selected = i;
+        }
+    }
+
+    /// Switches to the next list order, keeping the selected container selected.
+    pub fn cycle_sort(&mut self) {
+        let selected_id = self
+            .selected_container()
+            .map(|container| container.id.clone());
+        self.config.sort = self.config.sort.next();
+        self.resort(selected_id);
+    }
+
+    /// Pins the selected container to the top of the list, or unpins it.
+    pub fn toggle_pin(&mut self) {
+        let Some(container) = self.selected_container() else {
+            return;
+        };
+        let (id, name) = (container.id.clone(), container.names.clone());
+        self.notification = Some(if self.pinned.remove(&id) {
+            format!("Unpinned {name}")
+        } else {
+            self.pinned.insert(id.clone());
+            format!("Pinned {name}")
+        });
+        self.resort(Some(id));
+    }
+
+    /// Drops the pin of a container the daemon reported as removed.
+    pub fn forget_removed_container(&mut self, id: &str) {
+        self.pinned.remove(id);
+    }
+
+    /// Records the selection and pins to remember for the next run.
+    pub fn save_into(&self, saved: &mut SavedState) {
+        // A container picked on the command line isn't a selection worth keeping.
+        if !self.stats_only {
+            saved.selected_id = self
+                .selected_container()
+                .map(|container| container.id.clone());
+        }
+        // Only a complete, fresh list shows which pinned containers are gone;
+        // under a label filter the others are merely hidden.
+        let complete = self.label_filter.is_none() && self.refresh_error.is_none();
+        saved.pinned = self
+            .pinned
+            .iter()
+            .filter(|id| {
+                !complete
+                    || self
+                        .container_data
+                        .iter()
+                        .any(|container| &&container.id == id)
+            })
+            .cloned()
+            .collect();
+    }
+
+    fn resort(&mut self, selected_id: Option<String>) {
+        sort_containers(&mut self.container_data, self.config.sort, &self.pinned);
+        if let Some(id) = selected_id {
+            self.select_container_id(&id);
+        }
+    }
+
+    /// Replaces the container list, keeping the selection on the same container
+    /// if it is still present.
+    pub fn set_container_data(&mut self, mut container_data: Vec<ContainerInfo>) {
+        let selected_id = self
+            .selected_container()
+            .map(|container| container.id.clone());
+        sort_containers(&mut container_data, self.config.sort, &self.pinned);
+        self.record_cpu_history(&container_data);
+        self.announce_watched_exits(&container_data);
+        self.alert_crashes(&container_data);
+        self.container_data = container_data;
+        self.selected = selected_id
+            .and_then(|id| {
+                self.container_data
+                    .iter()
+                    .position(|container| container.id == id)
+            })
+            .unwrap_or(self.selected)
+            .min(self.container_data.len().saturating_sub(1));
+        self.keep_selection_visible();
+    }
+
+    /// Starts or stops watching the selected container for its exit.
+    pub fn toggle_watch(&mut self) {
+        let Some(container) = self.selected_container() else {
+            return;
+        };
+        let (id, name, running) = (
+            container.id.clone(),
+            container.names.clone(),
+            container.is_running(),
+        );
+        self.notification = Some(if self.watched.remove(&id) {
+            format!("Stopped watching {name}")
+        } else if running {
+            self.watched.insert(id);
+            format!("Watching {name}, you'll be told when it exits")
+        } else {
+            format!("{name} isn't running")
+        });
+    }
+
+    /// Notifies about watched containers that were running before this refresh
+    /// and aren't anymore, then stops watching them.
+    fn announce_watched_exits(&mut self, container_data: &[ContainerInfo]) {
+        let exited: Vec<&ContainerInfo> = self
+            .container_data
+            .iter()
+            .filter(|previous| self.watched.contains(&previous.id) && previous.is_running())
+            .filter(|previous| {
+                !container_data
+                    .iter()
+                    .any(|container| container.id == previous.id && container.is_running())
+            })
+            .collect();
+        if exited.is_empty() {
+            return;
+        }
+        let message = exited
+            .iter()
+            .map(|previous| {
+                match container_data
+                    .iter()
+                    .find(|container| container.id == previous.id)
+                {
+                    Some(container) => format!("{} exited: {}", previous.names, container.status),
+                    None => format!("{} is gone", previous.names),
                 }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let ids: Vec<String> = exited.iter().map(|previous| previous.id.clone()).collect();
+        for id in ids {
+            self.watched.remove(&id);
+        }
+        self.diagnostics.info(format!("Watch: {message}"));
+        self.notification = Some(format!("WATCH: {message}"));
+        self.ring_bell |= self.config.watch_bell;
+    }
+
+    /// Alerts about containers that exited with a non-zero code since the
+    /// previous refresh, at most once per cooldown per container.
+    fn alert_crashes(&mut self, container_data: &[ContainerInfo]) {
+        if self.config.crash_alert == CrashAlert::Off {
+            return;
+        }
+        let cooldown = Duration::from_secs(self.config.crash_alert_cooldown_secs);
+        self.crash_alerted
+            .retain(|_, alerted| alerted.elapsed() < cooldown);
+        let crashed: Vec<(&ContainerInfo, i64)> = container_data
+            .iter()
+            .filter_map(|container| Some((container, container.exit_code()?)))
+            .filter(|(container, code)| {
+                *code != 0
+                    && !self.crash_alerted.contains_key(&container.id)
+                    && self
+                        .container_data
+                        .iter()
+                        .any(|previous| previous.id == container.id && previous.state != "exited")
+            })
+            .collect();
+        if crashed.is_empty() {
+            return;
+        }
+        for (container, _) in &crashed {
+            self.crash_alerted
+                .insert(container.id.clone(), Instant::now());
+        }
+        let message = crashed
+            .iter()
+            .map(|(container, code)| {
+                format!(
+                    "{} crashed ({code})",
+                    container.names.trim_start_matches('/')
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.diagnostics.warn(format!("Crash: {message}"));
+        match self.config.crash_alert {
+            CrashAlert::Off => {}
+            CrashAlert::Bell => self.ring_bell = true,
+            CrashAlert::Desktop => self.desktop_alert = Some(message.clone()),
+        }
+        self.notification = Some(format!("CRASH: {message}"));
+    }
+
+    /// Applies the outcome of a refresh. On failure the last known list stays
+    /// visible and the error is shown until a refresh succeeds again.
+    pub fn apply_refresh(&mut self, result: Result<Vec<ContainerInfo>, String>) {
+        match result {
+            Ok(container_data) => {
+                self.diagnostics
+                    .debug(format!("Refreshed {} containers", container_data.len()));
+                self.refresh_error = None;
+                self.last_refresh = Some(Instant::now());
+                self.set_container_data(container_data);
+            }
+            Err(e) => {
+                self.diagnostics.error(format!("Refresh failed: {e}"));
+                self.refresh_error = Some(e);
+            }
+        }
+    }
+
+    /// Whether container refreshes need to sample per-container stats.
+    pub fn samples_usage(&self) -> bool {
+        self.show_memory || self.show_cpu || self.show_summary
+    }
+
+    fn record_cpu_history(&mut self, container_data: &[ContainerInfo]) {
+        self.cpu_history
+            .retain(|id, _| container_data.iter().any(|container| &container.id == id));
+        for container in container_data {
+            let usage = self
+                .container_data
+                .iter()
+                .find(|previous| previous.id == container.id)
+                .and_then(|previous| container.cpu_usage_since(previous));
+            if let Some(usage) = usage {
+                let history = self.cpu_history.entry(container.id.clone()).or_default();
+                let sample = history.data.back().map_or(0.0, |(x, _)| x + 1.0);
+                history.add((sample, usage));
             }
-            if binding.keys.contains(&key) {
-                (binding.action)(self, &key);
-                break;
+        }
+    }
+
+    /// Trailing log lines that arrived recently enough to be highlighted.
+    pub fn fresh_log_lines(&self) -> usize {
+        match self.last_log_flush {
+            Some((count, at)) if at.elapsed() < FRESH_LOG_DURATION => count.min(self.logs.len()),
+            _ => 0,
+        }
+    }
+
+    pub fn push_event(&mut self, at: i64, description: String) {
+        if self.events.len() == MAX_EVENTS {
+            self.events.pop_front();
+        }
+        self.events.push_back((at, description));
+    }
+
+    pub fn clear_search(&mut self) {
+        self.search_query.clear();
+        self.search_matches.clear();
+        self.current_match_index = None;
+    }
+
+    /// Aborts any running log or stats stream so at most one is alive at a time.
+    pub fn stop_streams(&mut self) {
+        self.stop_log_stream();
+        if let Some(handle) = self.stats_task.take() {
+            handle.abort();
+        }
+    }
+
+    pub fn stop_log_stream(&mut self) {
+        if let Some(handle) = self.log_task.take() {
+            handle.abort();
+        }
+    }
+
+    /// Leaves the current log stream running in the background, up to
+    /// `config.background_log_streams` of them, stopping the least recently
+    /// viewed one to make room. Streams that haven't delivered yet are stopped.
+    pub fn park_log_stream(&mut self) {
+        let capacity = self.config.background_log_streams;
+        let (Some(id), Some(task)) = (self.logs_for.take(), self.log_task.take()) else {
+            return;
+        };
+        if capacity == 0 || self.logs_loading {
+            task.abort();
+            return;
+        }
+        while self.parked_logs.len() >= capacity {
+            if let Some((_, evicted)) = self.parked_logs.pop_front() {
+                evicted.task.abort();
             }
         }
+        let logs = std::mem::take(&mut self.logs);
+        self.parked_logs.push_back((
+            id,
+            ParkedLogs {
+                logs,
+                truncated: std::mem::take(&mut self.logs_truncated),
+                last_log_time: self.last_log_time.take(),
+                task,
+            },
+        ));
+    }
+
+    /// Whether lines from `container_id` belong in `logs`; streams started
+    /// without a container to show count as shown, parked ones never do.
+    pub fn shows_logs_of(&self, container_id: &str) -> bool {
+        !self.parked_logs.iter().any(|(id, _)| id == container_id)
+            && self
+                .logs_for
+                .as_deref()
+                .is_none_or(|shown| shown == container_id)
+    }
+
+    /// Takes back the parked stream of `container_id`, if there is one.
+    pub fn unpark_logs(&mut self, container_id: &str) -> Option<ParkedLogs> {
+        let position = self
+            .parked_logs
+            .iter()
+            .position(|(id, _)| id == container_id)?;
+        self.parked_logs.remove(position).map(|(_, parked)| parked)
+    }
+
+    /// Stops the stats stream and forgets its samples, so the next container starts empty.
+    pub fn close_stats(&mut self) {
+        if let Some(handle) = self.stats_task.take() {
+            handle.abort();
+        }
+        self.cpu_data.clear();
+        self.mem_data.clear();
+        self.stats_message = None;
+        self.resource_limits = None;
+        self.online_cpus = None;
+    }
+
+    /// Captures the current graphs as the baseline, or drops the baseline if
+    /// the selected container already has one.
+    pub fn toggle_stats_baseline(&mut self) {
+        let Some(container_id) = self.selected_container().map(|c| c.id.clone()) else {
+            return;
+        };
+        if self
+            .stats_baseline
+            .take_if(|baseline| baseline.container_id == container_id)
+            .is_some()
+        {
+            self.notification = Some("Baseline cleared".to_string());
+        } else if self.cpu_data.data.is_empty() && self.mem_data.data.is_empty() {
+            self.notification = Some("No stats to capture as a baseline yet".to_string());
+        } else {
+            self.stats_baseline = Some(StatsBaseline {
+                container_id,
+                cpu: self.cpu_data.clone(),
+                mem: self.mem_data.clone(),
+            });
+            self.notification = Some("Captured the graphs as a baseline".to_string());
+        }
+    }
+
+    /// The baseline to draw behind the graphs of the selected container.
+    pub fn shown_baseline(&self) -> Option<&StatsBaseline> {
+        let container = self.selected_container()?;
+        self.stats_baseline
+            .as_ref()
+            .filter(|baseline| baseline.container_id == container.id)
+    }
+
+    /// Log lines covered by the visual selection, in order.
+    pub fn log_selection_range(&self) -> Option<RangeInclusive<usize>> {
+        let (anchor, cursor) = self.log_selection?;
+        let last = self.logs.len().checked_sub(1)?;
+        Some(anchor.min(cursor).min(last)..=anchor.max(cursor).min(last))
+    }
+
+    /// Whether resource graphs are on screen, alone or next to the logs.
+    pub fn shows_resources(&self) -> bool {
+        self.mode == AppMode::Resources || (self.mode == AppMode::Logs && self.split_view)
+    }
+
+    pub fn needs_stats_stream(&self) -> bool {
+        self.shows_resources() && self.stats_task.is_none()
+    }
+
+    /// Whether the logs overlay is waiting for a stream that hasn't been spawned yet.
+    /// Keys pressed before the first lines arrive must not start a second one.
+    pub fn needs_log_stream(&self) -> bool {
+        self.mode == AppMode::Logs
+            && (self.logs_loading || self.log_since.is_some())
+            && self.log_task.is_none()
+    }
+
+    /// The selected container if its log preview still has to be fetched.
+    pub fn preview_target(&self) -> Option<String> {
+        let container = self.selected_container().filter(|_| self.show_preview)?;
+        (self.preview_for.as_deref() != Some(container.id.as_str())).then(|| container.id.clone())
+    }
+
+    /// The running container whose usage the open context menu still has to sample.
+    pub fn menu_usage_target(&self) -> Option<String> {
+        if self.mode != AppMode::ContextMenu {
+            return None;
+        }
+        let container = self.selected_container().filter(|c| c.is_running())?;
+        (self.menu_usage_for.as_deref() != Some(container.id.as_str()))
+            .then(|| container.id.clone())
+    }
+
+    /// Forgets the menu's usage so the next open samples it afresh.
+    pub fn clear_menu_usage(&mut self) {
+        if let Some(handle) = self.menu_usage_task.take() {
+            handle.abort();
+        }
+        self.menu_usage_for = None;
+        self.menu_usage = None;
+    }
+
+    /// Remembers the tail of the open logs so the next open can tell what's new.
+    pub fn remember_seen_logs(&mut self) {
+        if self.logs_loading {
+            return;
+        }
+        let Some(id) = self
+            .selected_container()
+            .map(|container| container.id.clone())
+        else {
+            return;
+        };
+        let start = self.logs.len().saturating_sub(SEEN_FINGERPRINT_LINES);
+        self.seen_logs.insert(id, self.logs[start..].to_vec());
+    }
+
+    /// Finds where the lines seen on the previous visit end in the reloaded logs.
+    pub fn locate_unread(&mut self) {
+        if self.unread_from.is_some() {
+            return;
+        }
+        let Some(id) = self
+            .selected_container()
+            .map(|container| container.id.clone())
+        else {
+            return;
+        };
+        let Some(seen) = self.seen_logs.get(&id).filter(|seen| !seen.is_empty()) else {
+            return;
+        };
+        if let Some(position) = self
+            .logs
+            .windows(seen.len())
+            .rposition(|window| window == seen.as_slice())
+        {
+            self.unread_from = Some(position + seen.len());
+            self.seen_logs.remove(&id);
+        }
+    }
+
+    /// Scrolls the logs so `line` sits mid-view, clamped at both ends of the buffer.
+    pub fn center_log_line(&mut self, line: usize) {
+        let height = self.visible_height as usize;
+        let max_scroll = self.logs.len().saturating_sub(height);
+        self.vertical_scroll = line.saturating_sub(height / 2).min(max_scroll) as u16;
+    }
+
+    /// Scrolling down to within `follow_threshold` lines of the bottom resumes
+    /// following new log lines; anywhere else pins the view.
+    pub fn follow_if_near_bottom(&mut self) {
+        let bottom = self.logs.len().saturating_sub(self.visible_height as usize);
+        let distance = bottom.saturating_sub(self.vertical_scroll as usize);
+        self.user_scrolled = distance > self.config.follow_threshold;
+    }
+
+    /// Lines that arrived since the previous visit, if any.
+    pub fn unread_logs(&self) -> usize {
+        self.unread_from
+            .map_or(0, |from| self.logs.len().saturating_sub(from))
+    }
+
+    /// Moves the selection to the shown container whose name best matches the
+    /// quick-open input.
+    fn select_quick_open_match(&mut self) {
+        let query = self.quick_open_input.value();
+        if query.is_empty() {
+            return;
+        }
+        let best = self
+            .visible_containers()
+            .into_iter()
+            .filter_map(|i| {
+                let score = search::score(query, &self.container_data[i].names, self.fuzzy_search)?;
+                Some((i, score))
+            })
+            .max_by_key(|&(i, score)| (score, std::cmp::Reverse(i)));
+        if let Some((i, _)) = best {
+            self.selected = i;
+        }
+    }
+
+    /// The text input receiving typed characters in the current mode, if any.
+    pub fn active_input_mut(&mut self) -> Option<&mut TextInput> {
+        match self.mode {
+            AppMode::Search => Some(&mut self.search_query),
+            AppMode::Rename => Some(&mut self.rename_input),
+            AppMode::ConfirmRemove => Some(&mut self.confirm_input),
+            AppMode::ConfirmAction => Some(&mut self.timeout_input),
+            AppMode::QuickOpen => Some(&mut self.quick_open_input),
+            _ => None,
+        }
+    }
+
+    /// Context menu entries: the built-in actions, then the configured ones.
+    pub fn menu_len(&self) -> usize {
+        self.menu_items.len() + self.config.actions.len()
+    }
+
+    /// Index of the first menu entry activated by `key`.
+    fn menu_position(&self, key: char) -> Option<usize> {
+        let custom = self.config.actions.iter().map(|action| action.key);
+        self.menu_items
+            .iter()
+            .map(|item| item.key)
+            .chain(custom)
+            .position(|menu_key| menu_key == key)
+    }
+
+    pub fn handle_input(&mut self, key: impl Into<KeyEvent>) {
+        let key = key.into();
+        self.notification = None;
+        self.prompt_error = None;
+        if let Some(input) = self.active_input_mut()
+            && let KeyCode::Char(c) = key.code
+            && !key
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+        {
+            input.insert(c);
+        } else if self.mode == AppMode::ContextMenu
+            && let KeyCode::Char(c) = key.code
+            && let Some(index) = self.menu_position(c)
+        {
+            self.menu_selected = index;
+            activate_menu_item(self);
+        } else if let Some(binding) = default_keybindings()
+            .into_iter()
+            .find(|binding| binding.matches(&key))
+        {
+            (binding.action)(self, &key);
+        } else if self.mode == AppMode::Normal
+            && let KeyCode::Char(c) = key.code
+            && let Some(index) = self
+                .config
+                .actions
+                .iter()
+                .position(|action| action.key == c)
+        {
+            queue_custom_action(self, index);
+        }
+        if self.mode == AppMode::QuickOpen {
+            self.select_quick_open_match();
+        }
+        self.keep_selection_visible();
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::vec;
+    use std::{collections::BTreeSet, vec};
+
+    use bollard::container::{CPUStats, CPUUsage, ThrottlingData};
 
     use super::*;
+    use crate::config::{
+        ContainerSort, CpuScale, CrashAlert, CustomAction, EnterAction, LogAnchor,
+    };
+    use crate::docker::COMPOSE_PROJECT_LABEL;
 
     fn get_app_state() -> AppState {
         AppState {
             container_data: vec![
-                (
-                    "id1".to_string(),
-                    vec![
-                        "id1".into(),
-                        "img1".into(),
-                        "running".into(),
-                        "name1".into(),
-                        "127.0.0.1".into(),
-                    ],
-                ),
-                (
-                    "id2".to_string(),
-                    vec![
-                        "id2".into(),
-                        "img2".into(),
-                        "exited".into(),
-                        "name2".into(),
-                        "127.0.0.2".into(),
-                    ],
-                ),
+                ContainerInfo {
+                    id: "id1".into(),
+                    image: "img1".into(),
+                    state: "running".into(),
+                    status: "running".into(),
+                    names: "name1".into(),
+                    ip: "127.0.0.1".into(),
+                    ..Default::default()
+                },
+                ContainerInfo {
+                    id: "id2".into(),
+                    image: "img2".into(),
+                    state: "exited".into(),
+                    status: "exited".into(),
+                    names: "name2".into(),
+                    ip: "127.0.0.2".into(),
+                    ..Default::default()
+                },
             ],
             logs: std::iter::repeat_n("log_line".to_string(), 50).collect(),
             ..Default::default()
@@ -107,7 +924,7 @@ mod tests {
         };
 
         app.handle_input(KeyCode::Esc);
-        assert_eq!(false, app.running);
+        assert!(!app.running);
     }
 
     #[test]
@@ -115,106 +932,1470 @@ mod tests {
         let mut app = get_app_state();
         app.mode = AppMode::Search;
         app.last_mode = AppMode::Logs;
-        app.search_query = "test".to_string();
+        app.search_query = "test".into();
         app.handle_input(KeyCode::Esc);
         assert_eq!(AppMode::Logs, app.mode);
-        assert_eq!("".to_string(), app.search_query);
+        assert_eq!("", app.search_query.value());
     }
 
     #[test]
-    fn enter_exits_search_input() {
+    fn esc_exits_normal_mode_search_to_normal_mode() {
         let mut app = get_app_state();
-        app.mode = AppMode::Search;
-        app.last_mode = AppMode::Logs;
-        app.search_query = "test".to_string();
+        app.handle_input(KeyCode::Char('/'));
+        app.handle_input(KeyCode::Char('i'));
+        app.handle_input(KeyCode::Esc);
+        assert_eq!(AppMode::Normal, app.mode);
+        assert!(app.running);
+        assert!(app.search_query.is_empty());
+    }
+
+    #[test]
+    fn normal_mode_search_filters_table() {
+        let mut app = get_app_state();
+        app.handle_input(KeyCode::Char('/'));
+        app.handle_input(KeyCode::Char('n'));
+        app.handle_input(KeyCode::Char('2'));
+        assert_eq!(vec![1], app.visible_containers());
+        assert_eq!("id2", app.selected_container().unwrap().id);
+
         app.handle_input(KeyCode::Enter);
-        assert_eq!(AppMode::Logs, app.mode);
-        assert_eq!("test".to_string(), app.search_query);
+        assert_eq!(AppMode::Normal, app.mode);
+        assert_eq!(vec![1], app.visible_containers());
+
+        app.handle_input(KeyCode::Esc);
+        assert!(app.running);
+        assert_eq!(vec![0, 1], app.visible_containers());
     }
 
     #[test]
-    fn bound_keys_can_be_entered_in_search() {
+    fn esc_in_normal_mode_search_restores_table() {
         let mut app = get_app_state();
-        app.mode = AppMode::Search;
-        app.handle_input(KeyCode::Char('h'));
-        assert_eq!("h".to_string(), app.search_query);
+        app.table_filter = Some("name1".into());
+        app.handle_input(KeyCode::Char('/'));
+        app.handle_input(KeyCode::Char('x'));
+        assert!(app.visible_containers().is_empty());
+        assert!(app.selected_container().is_none());
+
+        app.handle_input(KeyCode::Esc);
+        assert_eq!(vec![0, 1], app.visible_containers());
     }
 
     #[test]
-    fn question_mark_opens_help() {
+    fn filtered_table_sorts_by_score() {
         let mut app = get_app_state();
-        app.mode = AppMode::Logs;
-        app.handle_input(KeyCode::Char('?'));
-        assert_eq!(AppMode::Help, app.mode);
+        app.container_data[0].names = "worker-backend".into();
+        app.container_data[1].names = "web".into();
+        app.table_filter = Some("web".into());
+        assert_eq!(vec![1, 0], app.visible_containers());
     }
 
     #[test]
-    fn enter_opens_context_menu() {
+    fn ctrl_f_toggles_literal_search() {
+        let mut app = get_app_state();
+        app.table_filter = Some("nm1".into());
+        assert_eq!(vec![0], app.visible_containers());
+
+        app.handle_input(KeyEvent::new(KeyCode::Char('f'), KeyModifiers::CONTROL));
+        assert!(!app.fuzzy_search);
+        assert!(app.visible_containers().is_empty());
+    }
+
+    #[test]
+    fn log_search_starts_at_best_match() {
         let mut app = get_app_state();
+        app.logs = vec![
+            "server restarted".to_string(),
+            "fine".to_string(),
+            "error".to_string(),
+        ];
+        app.last_mode = AppMode::Logs;
+        app.mode = AppMode::Search;
+        app.search_query = "err".into();
         app.handle_input(KeyCode::Enter);
-        assert_eq!(AppMode::ContextMenu, app.mode);
+        assert_eq!(vec![0, 2], app.search_matches);
+        assert_eq!(Some(1), app.current_match_index);
+        assert_eq!(2, app.vertical_scroll);
     }
 
     #[test]
-    fn enter_opens_logs() {
+    fn arrows_skip_filtered_rows() {
         let mut app = get_app_state();
-        app.mode = AppMode::ContextMenu;
-        app.menu_selected = 1;
+        app.container_data.push(ContainerInfo {
+            id: "id3".into(),
+            names: "name3".into(),
+            ..Default::default()
+        });
+        app.table_filter = Some("nm".into());
+        app.container_data[1].names = "other".into();
+        app.handle_input(KeyCode::Down);
+        assert_eq!(2, app.selected);
         app.handle_input(KeyCode::Up);
-        app.handle_input(KeyCode::Enter);
+        assert_eq!(0, app.selected);
+    }
+
+    #[test]
+    fn menu_accelerators_activate_items() {
+        let mut app = get_app_state();
+        app.mode = AppMode::ContextMenu;
+        app.handle_input(KeyCode::Char('x'));
+        assert_eq!(AppMode::ConfirmRemove, app.mode);
+
+        app.mode = AppMode::ContextMenu;
+        app.handle_input(KeyCode::Char('l'));
         assert_eq!(AppMode::Logs, app.mode);
+        assert_eq!(0, app.menu_selected);
     }
 
     #[test]
-    fn slash_opens_search() {
+    fn split_view_streams_logs_and_stats_together() {
         let mut app = get_app_state();
-        app.mode = AppMode::Logs;
-        app.handle_input(KeyCode::Char('/'));
-        app.handle_input(KeyCode::Char('a'));
-        assert_eq!(AppMode::Search, app.mode);
-        assert_eq!("a".to_string(), app.search_query);
+        app.mode = AppMode::ContextMenu;
+        app.handle_input(KeyCode::Char('o'));
+        assert_eq!(AppMode::Logs, app.mode);
+        assert!(app.split_view);
+        assert!(app.needs_log_stream());
+        assert!(app.needs_stats_stream());
+
+        // Resource keys reach the graphs while the logs keep their own.
+        app.handle_input(KeyCode::Char('2'));
+        assert_eq!(Some(Metric::Mem), app.focused_metric);
+        app.handle_input(KeyCode::Char('j'));
+        assert_eq!(1, app.vertical_scroll);
+
+        app.cpu_data.add((1.0, 10.0));
+        app.handle_input(KeyCode::Char('S'));
+        assert!(!app.split_view);
+        assert!(!app.needs_stats_stream());
+        assert!(app.cpu_data.data.is_empty());
+
+        app.handle_input(KeyCode::Char('S'));
+        app.cpu_data.add((1.0, 10.0));
+        app.handle_input(KeyCode::Esc);
+        assert_eq!(AppMode::Normal, app.mode);
+        assert!(app.cpu_data.data.is_empty());
+        app.mode = AppMode::ContextMenu;
+        app.handle_input(KeyCode::Char('l'));
+        assert!(!app.split_view);
     }
 
     #[test]
-    fn jumps_to_next_match_logs_mode() {
+    fn tab_cycles_focused_metric() {
         let mut app = get_app_state();
-        app.mode = AppMode::Logs;
-        app.search_matches = vec![1, 2];
-        app.current_match_index = Some(0);
-        app.handle_input(KeyCode::Char('n'));
-        assert_eq!(Some(1), app.current_match_index);
-        assert_eq!(2, app.vertical_scroll);
+        app.mode = AppMode::Resources;
+        app.handle_input(KeyCode::Tab);
+        assert_eq!(Some(Metric::Cpu), app.focused_metric);
+        app.handle_input(KeyCode::Tab);
+        assert_eq!(Some(Metric::Mem), app.focused_metric);
+        app.handle_input(KeyCode::Tab);
+        assert_eq!(None, app.focused_metric);
     }
-    
+
     #[test]
-    fn jumps_to_previous_match_logs_mode() {
+    fn number_keys_expand_a_metric() {
         let mut app = get_app_state();
-        app.mode = AppMode::Logs;
-        app.search_matches = vec![1, 2];
-        app.current_match_index = Some(1);
-        app.handle_input(KeyCode::Char('N'));
-        assert_eq!(Some(0), app.current_match_index);
-        assert_eq!(1, app.vertical_scroll);
+        app.mode = AppMode::Resources;
+        app.handle_input(KeyCode::Char('2'));
+        assert_eq!(Some(Metric::Mem), app.focused_metric);
+        app.handle_input(KeyCode::Char('3'));
+        assert_eq!(Some(Metric::Mem), app.focused_metric);
+        app.handle_input(KeyCode::Char('0'));
+        assert_eq!(None, app.focused_metric);
     }
-    
+
     #[test]
-    fn jump_to_next_match_normal_mode() {
+    fn b_captures_and_clears_a_stats_baseline() {
         let mut app = get_app_state();
-        app.mode = AppMode::Normal;
-        app.search_matches = vec![1, 2];
-        app.current_match_index = Some(0);
-        app.handle_input(KeyCode::Char('n'));
-        assert_eq!(Some(1), app.current_match_index);
+        app.mode = AppMode::Resources;
+        app.handle_input(KeyCode::Char('B'));
+        assert!(app.stats_baseline.is_none());
+
+        app.cpu_data.add((1.0, 30.0));
+        app.handle_input(KeyCode::Char('B'));
+        let baseline = app.shown_baseline().unwrap();
+        assert_eq!("id1", baseline.container_id);
+        assert_eq!(Some(30.0), baseline.cpu.get_max());
+
+        // Other containers don't compare against it.
+        app.selected = 1;
+        assert!(app.shown_baseline().is_none());
+        app.selected = 0;
+
+        app.handle_input(KeyCode::Char('B'));
+        assert!(app.stats_baseline.is_none());
     }
-    
+
     #[test]
-    fn jump_to_previous_match_normal_mode() {
+    fn u_switches_the_cpu_scale_in_resources() {
         let mut app = get_app_state();
-        app.mode = AppMode::Normal;
-        app.search_matches = vec![1, 2];
+        app.handle_input(KeyCode::Char('u'));
+        assert_eq!(CpuScale::Aggregate, app.config.cpu_scale);
+        app.mode = AppMode::Resources;
+        app.handle_input(KeyCode::Char('u'));
+        assert_eq!(CpuScale::Normalized, app.config.cpu_scale);
+        app.handle_input(KeyCode::Char('u'));
+        assert_eq!(CpuScale::Aggregate, app.config.cpu_scale);
+    }
+
+    #[test]
+    fn y_queues_docker_commands_for_copying() {
+        let mut app = get_app_state();
+        app.handle_input(KeyCode::Char('y'));
+        assert_eq!(Some("docker logs -f id1".to_string()), app.pending_copy);
+        app.handle_input(KeyCode::Char('Y'));
+        assert_eq!(Some("docker exec -it id1 sh".to_string()), app.pending_copy);
+    }
+
+    #[test]
+    fn visual_selection_copies_a_range_of_log_lines() {
+        let mut app = get_app_state();
+        app.mode = AppMode::Logs;
+        app.visible_height = 2;
+        app.logs = vec![
+            "one\n".into(),
+            "\x1b[31mtwo\x1b[0m\n".into(),
+            "three\n".into(),
+        ];
+        app.handle_input(KeyCode::Char('v'));
+        assert_eq!(Some((0, 0)), app.log_selection);
+        app.handle_input(KeyCode::Char('j'));
+        app.handle_input(KeyCode::Char('j'));
+        app.handle_input(KeyCode::Char('j'));
+        assert_eq!(Some(0..=2), app.log_selection_range());
+        assert_eq!(1, app.vertical_scroll);
+
+        app.handle_input(KeyCode::Char('k'));
+        app.handle_input(KeyCode::Char('y'));
+        assert_eq!(Some("one\ntwo".to_string()), app.pending_copy);
+        assert_eq!(None, app.log_selection);
+    }
+
+    #[test]
+    fn esc_cancels_a_visual_selection_before_closing_logs() {
+        let mut app = get_app_state();
+        app.mode = AppMode::Logs;
+        app.handle_input(KeyCode::Char('v'));
+        app.handle_input(KeyCode::Esc);
+        assert_eq!(AppMode::Logs, app.mode);
+        assert_eq!(None, app.log_selection);
+
+        app.collapse_duplicates = true;
+        app.handle_input(KeyCode::Char('v'));
+        assert_eq!(None, app.log_selection);
+        assert!(app.notification.is_some());
+    }
+
+    #[test]
+    fn quick_open_moves_selection_as_you_type() {
+        let mut app = get_app_state();
+        app.handle_input(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::CONTROL));
+        assert_eq!(AppMode::QuickOpen, app.mode);
+        app.handle_input(KeyCode::Char('n'));
+        app.handle_input(KeyCode::Char('2'));
+        assert_eq!(1, app.selected);
+        assert_eq!(2, app.visible_containers().len());
+
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(AppMode::Normal, app.mode);
+        assert_eq!(1, app.selected);
+        assert!(app.quick_open_input.is_empty());
+    }
+
+    #[test]
+    fn esc_cancels_quick_open() {
+        let mut app = get_app_state();
+        app.handle_input(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::CONTROL));
+        app.handle_input(KeyCode::Char('2'));
+        assert_eq!(1, app.selected);
+        app.handle_input(KeyCode::Esc);
+        assert_eq!(AppMode::Normal, app.mode);
+        assert_eq!(0, app.selected);
+        assert!(app.running);
+    }
+
+    #[test]
+    fn enter_exits_search_input() {
+        let mut app = get_app_state();
+        app.mode = AppMode::Search;
+        app.last_mode = AppMode::Logs;
+        app.search_query = "test".into();
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(AppMode::Logs, app.mode);
+        assert_eq!("test", app.search_query.value());
+    }
+
+    #[test]
+    fn bound_keys_can_be_entered_in_search() {
+        let mut app = get_app_state();
+        app.mode = AppMode::Search;
+        app.handle_input(KeyCode::Char('h'));
+        assert_eq!("h", app.search_query.value());
+    }
+
+    #[test]
+    fn arrow_keys_move_search_cursor() {
+        let mut app = get_app_state();
+        app.mode = AppMode::Search;
+        app.search_query = "ac".into();
+        app.handle_input(KeyCode::Left);
+        app.handle_input(KeyCode::Char('b'));
+        app.handle_input(KeyCode::Home);
+        app.handle_input(KeyCode::Char('_'));
+        app.handle_input(KeyCode::End);
+        app.handle_input(KeyCode::Backspace);
+        assert_eq!("_ab", app.search_query.value());
+    }
+
+    #[test]
+    fn question_mark_opens_help() {
+        let mut app = get_app_state();
+        app.mode = AppMode::Logs;
+        app.handle_input(KeyCode::Char('?'));
+        assert_eq!(AppMode::Help, app.mode);
+    }
+
+    #[test]
+    fn enter_opens_context_menu() {
+        let mut app = get_app_state();
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(AppMode::ContextMenu, app.mode);
+    }
+
+    #[test]
+    fn enter_opens_logs() {
+        let mut app = get_app_state();
+        app.mode = AppMode::ContextMenu;
+        app.menu_selected = 1;
+        app.handle_input(KeyCode::Up);
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(AppMode::Logs, app.mode);
+    }
+
+    #[tokio::test]
+    async fn opening_logs_aborts_stats_stream() {
+        let mut app = get_app_state();
+        let stats_task = tokio::spawn(futures::future::pending::<()>());
+        let abort_handle = stats_task.abort_handle();
+        app.stats_task = Some(stats_task);
+        app.mode = AppMode::ContextMenu;
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(AppMode::Logs, app.mode);
+        assert!(app.stats_task.is_none());
+        tokio::task::yield_now().await;
+        assert!(abort_handle.is_finished());
+    }
+
+    #[tokio::test]
+    async fn opening_stats_aborts_log_stream() {
+        let mut app = get_app_state();
+        let log_task = tokio::spawn(futures::future::pending::<()>());
+        let abort_handle = log_task.abort_handle();
+        app.log_task = Some(log_task);
+        app.mode = AppMode::ContextMenu;
+        app.menu_selected = 1;
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(AppMode::Resources, app.mode);
+        assert!(app.log_task.is_none());
+        tokio::task::yield_now().await;
+        assert!(abort_handle.is_finished());
+    }
+
+    #[tokio::test]
+    async fn closed_logs_keep_streaming_in_the_background() {
+        let mut app = get_app_state();
+        app.config.background_log_streams = 1;
+        let open_logs = |app: &mut AppState, lines: &[&str]| {
+            app.mode = AppMode::ContextMenu;
+            app.menu_selected = 0;
+            app.handle_input(KeyCode::Enter);
+            assert!(app.log_task.is_none());
+            let task = tokio::spawn(futures::future::pending::<()>());
+            let abort_handle = task.abort_handle();
+            app.log_task = Some(task);
+            app.logs = lines.iter().map(|line| line.to_string()).collect();
+            app.logs_loading = false;
+            app.handle_input(KeyCode::Esc);
+            abort_handle
+        };
+
+        let first = open_logs(&mut app, &["one"]);
+        assert_eq!(
+            vec!["id1"],
+            app.parked_logs
+                .iter()
+                .map(|(id, _)| id.as_str())
+                .collect::<Vec<_>>()
+        );
+        app.selected = 1;
+        let second = open_logs(&mut app, &["two"]);
+        assert_eq!(
+            vec!["id2"],
+            app.parked_logs
+                .iter()
+                .map(|(id, _)| id.as_str())
+                .collect::<Vec<_>>()
+        );
+        tokio::task::yield_now().await;
+        assert!(first.is_finished());
+        assert!(!second.is_finished());
+
+        app.mode = AppMode::ContextMenu;
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(vec!["two"], app.logs);
+        assert!(!app.logs_loading);
+        assert!(app.log_task.is_some());
+        assert!(app.parked_logs.is_empty());
+    }
+
+    #[tokio::test]
+    async fn closed_logs_stop_without_background_streams() {
+        let mut app = get_app_state();
+        app.mode = AppMode::ContextMenu;
+        app.handle_input(KeyCode::Enter);
+        let task = tokio::spawn(futures::future::pending::<()>());
+        let abort_handle = task.abort_handle();
+        app.log_task = Some(task);
+        app.logs_loading = false;
+        app.handle_input(KeyCode::Esc);
+        assert!(app.parked_logs.is_empty());
+        tokio::task::yield_now().await;
+        assert!(abort_handle.is_finished());
+    }
+
+    #[tokio::test]
+    async fn ctrl_l_clears_logs_but_keeps_streaming() {
+        let mut app = get_app_state();
+        app.mode = AppMode::Logs;
+        app.vertical_scroll = 30;
+        app.user_scrolled = true;
+        let task = tokio::spawn(futures::future::pending::<()>());
+        let abort_handle = task.abort_handle();
+        app.log_task = Some(task);
+        app.handle_input(KeyEvent::new(KeyCode::Char('l'), KeyModifiers::CONTROL));
+        assert!(app.logs.is_empty());
+        assert_eq!(0, app.vertical_scroll);
+        assert!(!app.user_scrolled);
+        assert!(app.log_task.is_some());
+        tokio::task::yield_now().await;
+        assert!(!abort_handle.is_finished());
+    }
+
+    #[test]
+    fn enter_runs_configured_action() {
+        let mut app = get_app_state();
+        app.config.enter_action = EnterAction::Logs;
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(AppMode::Logs, app.mode);
+        assert_eq!(vec![LOADING_LOGS.to_string()], app.logs);
+        assert!(app.logs_loading);
+
+        let mut app = get_app_state();
+        app.config.enter_action = EnterAction::Details;
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(AppMode::Details, app.mode);
+    }
+
+    #[tokio::test]
+    async fn log_stream_is_started_once_per_open() {
+        let mut app = get_app_state();
+        app.mode = AppMode::ContextMenu;
+        app.handle_input(KeyCode::Enter);
+        assert!(app.needs_log_stream());
+
+        app.log_task = Some(tokio::spawn(futures::future::pending::<()>()));
+        app.handle_input(KeyCode::Down);
+        assert!(app.logs_loading);
+        assert!(!app.needs_log_stream());
+
+        app.handle_input(KeyCode::Esc);
+        app.handle_input(KeyCode::Enter);
+        app.handle_input(KeyCode::Enter);
+        assert!(app.needs_log_stream());
+    }
+
+    #[test]
+    fn enter_opens_details() {
+        let mut app = get_app_state();
+        app.mode = AppMode::ContextMenu;
+        app.menu_selected = 2;
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(AppMode::Details, app.mode);
+        app.handle_input(KeyCode::Esc);
+        assert_eq!(AppMode::Normal, app.mode);
+    }
+
+    #[test]
+    fn refresh_keeps_selection_on_same_container() {
+        let mut app = get_app_state();
+        app.selected = 1;
+        let mut reordered = app.container_data.clone();
+        reordered.reverse();
+        reordered.push(ContainerInfo {
+            id: "id3".into(),
+            ..Default::default()
+        });
+        app.set_container_data(reordered);
+        assert_eq!("id2", app.container_data[app.selected].id);
+
+        app.selected = 2;
+        app.set_container_data(vec![ContainerInfo {
+            id: "id1".into(),
+            ..Default::default()
+        }]);
+        assert_eq!(0, app.selected);
+    }
+
+    #[test]
+    fn finds_containers_by_id_prefix_or_name() {
+        let mut app = get_app_state();
+        app.container_data[1].names = "/db, /db-alias".into();
+        assert_eq!(Some(0), app.find_container("id1"));
+        assert_eq!(Some(1), app.find_container("db-alias"));
+        assert_eq!(None, app.find_container("d"));
+    }
+
+    #[test]
+    fn closing_stats_only_mode_quits() {
+        let mut app = get_app_state();
+        app.mode = AppMode::Resources;
+        app.stats_only = true;
+        app.handle_input(KeyCode::Esc);
+        assert!(!app.running);
+    }
+
+    #[test]
+    fn restores_a_remembered_selection_if_it_still_exists() {
+        let mut app = get_app_state();
+        app.select_container_id("id2");
+        assert_eq!(1, app.selected);
+        app.select_container_id("gone");
+        assert_eq!(1, app.selected);
+    }
+
+    #[test]
+    fn sorts_by_creation_time_and_keeps_the_selection() {
+        let mut app = get_app_state();
+        app.container_data[0].created = Some(100);
+        app.container_data[1].created = Some(200);
+        app.handle_input(KeyCode::Char('o'));
+        assert_eq!(ContainerSort::Newest, app.config.sort);
+        assert_eq!("id2", app.container_data[0].id);
+        assert_eq!("id1", app.container_data[app.selected].id);
+
+        let mut refreshed = app.container_data.clone();
+        refreshed.reverse();
+        app.set_container_data(refreshed);
+        assert_eq!("id2", app.container_data[0].id);
+
+        app.handle_input(KeyCode::Char('o'));
+        assert_eq!("id1", app.container_data[0].id);
+        app.handle_input(KeyCode::Char('o'));
+        assert_eq!(ContainerSort::Name, app.config.sort);
+        assert_eq!("name1", app.container_data[0].names);
+    }
+
+    #[test]
+    fn pinned_containers_stay_on_top_in_any_order() {
+        let mut app = get_app_state();
+        app.container_data[0].created = Some(200);
+        app.container_data[1].created = Some(100);
+        app.selected = 1;
+        app.mode = AppMode::ContextMenu;
+        app.handle_input(KeyCode::Char('p'));
+        assert!(app.pinned.contains("id2"));
+        assert_eq!("id2", app.container_data[0].id);
+        assert_eq!(0, app.selected);
+
+        app.handle_input(KeyCode::Char('o'));
+        assert_eq!(ContainerSort::Newest, app.config.sort);
+        assert_eq!("id2", app.container_data[0].id);
+        let refreshed = app.container_data.clone();
+        app.set_container_data(refreshed);
+        assert_eq!("id2", app.container_data[0].id);
+
+        app.toggle_pin();
+        assert!(app.pinned.is_empty());
+        assert_eq!("id1", app.container_data[0].id);
+    }
+
+    #[test]
+    fn saving_under_a_label_filter_keeps_hidden_pins() {
+        let mut app = get_app_state();
+        app.pinned = HashSet::from(["id1".to_string(), "elsewhere".to_string()]);
+        app.label_filter = Some("app=web".into());
+        let mut saved = SavedState::default();
+        app.save_into(&mut saved);
+        assert_eq!(
+            BTreeSet::from(["elsewhere".to_string(), "id1".to_string()]),
+            saved.pinned
+        );
+
+        app.forget_removed_container("elsewhere");
+        app.save_into(&mut saved);
+        assert_eq!(BTreeSet::from(["id1".to_string()]), saved.pinned);
+
+        // Without a filter the list is complete, so missing pins are stale.
+        app.label_filter = None;
+        app.pinned.insert("gone".into());
+        app.save_into(&mut saved);
+        assert_eq!(BTreeSet::from(["id1".to_string()]), saved.pinned);
+        assert_eq!(Some("id1".to_string()), saved.selected_id);
+    }
+
+    #[test]
+    fn failed_refresh_keeps_last_known_containers() {
+        let mut app = get_app_state();
+        app.apply_refresh(Err("daemon busy".into()));
+        assert_eq!(2, app.container_data.len());
+        assert_eq!(Some("daemon busy"), app.refresh_error.as_deref());
+        assert_eq!(None, app.last_refresh);
+
+        app.apply_refresh(Ok(vec![ContainerInfo::default()]));
+        assert_eq!(1, app.container_data.len());
+        assert_eq!(None, app.refresh_error);
+        assert!(app.last_refresh.is_some());
+    }
+
+    #[test]
+    fn failed_refresh_is_diagnosed() {
+        let mut app = get_app_state();
+        app.apply_refresh(Err("daemon busy".into()));
+        let messages: Vec<&str> = app
+            .diagnostics
+            .entries()
+            .map(|diagnostic| diagnostic.message.as_str())
+            .collect();
+        assert_eq!(vec!["Refresh failed: daemon busy"], messages);
+
+        app.handle_input(KeyCode::Char('!'));
+        assert_eq!(AppMode::Diagnostics, app.mode);
+        app.handle_input(KeyCode::Esc);
+        assert_eq!(AppMode::Normal, app.mode);
+    }
+
+    #[test]
+    fn x_expands_long_log_lines() {
+        let mut app = get_app_state();
+        app.handle_input(KeyCode::Char('x'));
+        assert!(!app.expand_long_lines);
+        app.mode = AppMode::Logs;
+        app.handle_input(KeyCode::Char('x'));
+        assert!(app.expand_long_lines);
+        app.handle_input(KeyCode::Char('x'));
+        assert!(!app.expand_long_lines);
+    }
+
+    fn diagnose_action(capture: bool) -> CustomAction {
+        CustomAction {
+            label: "Diagnose".into(),
+            key: 'g',
+            command: "docker exec {id} diagnose.sh {name}".into(),
+            capture,
+        }
+    }
+
+    #[test]
+    fn custom_actions_follow_the_built_in_menu() {
+        let mut app = get_app_state();
+        app.config.actions = vec![diagnose_action(true)];
+        app.mode = AppMode::ContextMenu;
+        app.handle_input(KeyCode::Up);
+        assert_eq!(app.menu_items.len(), app.menu_selected);
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(AppMode::Normal, app.mode);
+        assert_eq!(
+            Some(("docker exec id1 diagnose.sh name1".to_string(), true)),
+            app.pending_command
+        );
+
+        app.pending_command = None;
+        app.mode = AppMode::ContextMenu;
+        app.handle_input(KeyCode::Char('g'));
+        assert!(app.pending_command.is_some());
+    }
+
+    #[test]
+    fn custom_action_keys_work_on_the_list() {
+        let mut app = get_app_state();
+        app.config.actions = vec![
+            diagnose_action(false),
+            CustomAction {
+                key: 'q',
+                ..diagnose_action(false)
+            },
+        ];
+        app.selected = 1;
+        app.handle_input(KeyCode::Char('g'));
+        assert_eq!(
+            Some(("docker exec id2 diagnose.sh name2".to_string(), false)),
+            app.pending_command
+        );
+
+        // Built-in keys take precedence.
+        app.pending_command = None;
+        app.handle_input(KeyCode::Char('q'));
+        assert!(!app.running);
+        assert_eq!(None, app.pending_command);
+    }
+
+    #[test]
+    fn menu_samples_usage_of_running_containers_once() {
+        let mut app = get_app_state();
+        assert_eq!(None, app.menu_usage_target());
+        app.mode = AppMode::ContextMenu;
+        assert_eq!(Some("id1".to_string()), app.menu_usage_target());
+        app.menu_usage_for = Some("id1".to_string());
+        assert_eq!(None, app.menu_usage_target());
+
+        app.clear_menu_usage();
+        app.selected = 1;
+        assert_eq!(None, app.menu_usage_target());
+    }
+
+    #[test]
+    fn enter_ignores_empty_container_list() {
+        let mut app = AppState::default();
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(AppMode::Normal, app.mode);
+
+        app.mode = AppMode::Rename;
+        app.rename_input.set("web".to_string());
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(None, app.pending_rename);
+    }
+
+    fn with_cpu_time(id: &str, total_usage: u64, system_cpu_usage: u64) -> ContainerInfo {
+        ContainerInfo {
+            id: id.into(),
+            cpu_stats: Some(CPUStats {
+                cpu_usage: CPUUsage {
+                    percpu_usage: None,
+                    usage_in_usermode: 0,
+                    total_usage,
+                    usage_in_kernelmode: 0,
+                },
+                system_cpu_usage: Some(system_cpu_usage),
+                online_cpus: Some(1),
+                throttling_data: ThrottlingData {
+                    periods: 0,
+                    throttled_periods: 0,
+                    throttled_time: 0,
+                },
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn refresh_records_cpu_history() {
+        let mut app = AppState::default();
+        app.set_container_data(vec![with_cpu_time("a", 0, 0), with_cpu_time("b", 0, 0)]);
+        assert!(app.cpu_history.is_empty());
+
+        app.set_container_data(vec![with_cpu_time("a", 25, 100), with_cpu_time("b", 0, 0)]);
+        let samples: Vec<f64> = app.cpu_history["a"].data.iter().map(|(_, y)| *y).collect();
+        assert_eq!(vec![25.0], samples);
+        assert!(!app.cpu_history.contains_key("b"));
+
+        app.set_container_data(vec![with_cpu_time("b", 0, 0)]);
+        assert!(app.cpu_history.is_empty());
+    }
+
+    #[test]
+    fn fresh_log_lines_expire() {
+        let mut app = get_app_state();
+        assert_eq!(0, app.fresh_log_lines());
+        app.last_log_flush = Some((3, Instant::now()));
+        assert_eq!(3, app.fresh_log_lines());
+        app.last_log_flush = Some((100, Instant::now()));
+        assert_eq!(50, app.fresh_log_lines());
+        app.last_log_flush = Instant::now()
+            .checked_sub(FRESH_LOG_DURATION)
+            .map(|at| (3, at));
+        assert_eq!(0, app.fresh_log_lines());
+    }
+
+    #[test]
+    fn scrolling_near_the_bottom_resumes_following() {
+        let mut app = get_app_state();
+        app.mode = AppMode::Logs;
+        app.visible_height = 10;
+        app.vertical_scroll = 36;
+        app.user_scrolled = true;
+        app.handle_input(KeyCode::Down);
+        assert!(app.user_scrolled);
+        app.handle_input(KeyCode::Down);
+        assert!(!app.user_scrolled);
+
+        app.config.follow_threshold = 0;
+        app.vertical_scroll = 38;
+        app.handle_input(KeyCode::Down);
+        assert!(app.user_scrolled);
+    }
+
+    #[test]
+    fn log_anchor_decides_whether_logs_follow() {
+        let mut app = get_app_state();
+        app.user_scrolled = true;
+        app.handle_input(KeyCode::Enter);
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(AppMode::Logs, app.mode);
+        assert!(!app.user_scrolled);
+
+        app.handle_input(KeyCode::Esc);
+        app.config.log_anchor = LogAnchor::Top;
+        app.handle_input(KeyCode::Enter);
+        app.handle_input(KeyCode::Enter);
+        assert!(app.user_scrolled);
+        assert_eq!(0, app.vertical_scroll);
+    }
+
+    #[test]
+    fn r_reconnects_the_log_stream() {
+        let mut app = get_app_state();
+        app.mode = AppMode::Logs;
+        app.log_error = Some("Error streaming logs: gone".into());
+        let last_line = DateTime::from_timestamp(1_700_000_000, 123_456_789).unwrap();
+        app.last_log_time = Some(last_line);
+        app.handle_input(KeyCode::Char('r'));
+        assert!(app.needs_log_stream());
+        assert_eq!(Some(last_line), app.log_since);
+        assert_eq!(50, app.logs.len());
+        assert_eq!(None, app.log_error);
+
+        app.log_since = None;
+        app.config.reconnect_clears_logs = true;
+        app.handle_input(KeyCode::Char('r'));
+        assert!(app.needs_log_stream());
+        assert_eq!(None, app.log_since);
+        assert_eq!(vec![LOADING_LOGS.to_string()], app.logs);
+    }
+
+    #[test]
+    fn f_toggles_the_log_filter_and_refetches() {
+        let mut app = get_app_state();
+        app.mode = AppMode::Logs;
+        app.handle_input(KeyCode::Char('f'));
+        assert!(app.log_filter_enabled);
+        assert_eq!(50, app.logs.len());
+
+        app.config.log_filter.exclude = vec!["health".into()];
+        app.hidden_log_lines = 3;
+        app.handle_input(KeyCode::Char('f'));
+        assert!(!app.log_filter_enabled);
+        assert_eq!(0, app.hidden_log_lines);
+        assert!(app.needs_log_stream());
+        app.handle_input(KeyCode::Char('f'));
+        assert!(app.log_filter_enabled);
+    }
+
+    #[test]
+    fn ctrl_d_and_ctrl_u_scroll_half_a_page() {
+        let mut app = get_app_state();
+        app.mode = AppMode::Logs;
+        app.visible_height = 10;
+        app.handle_input(KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL));
+        assert_eq!(5, app.vertical_scroll);
+        assert!(app.user_scrolled);
+        app.handle_input(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL));
+        app.handle_input(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL));
+        assert_eq!(0, app.vertical_scroll);
+
+        // Without the modifier, `d` keeps toggling duplicate collapsing.
+        app.handle_input(KeyCode::Char('d'));
+        assert_eq!(0, app.vertical_scroll);
+        assert!(app.collapse_duplicates);
+    }
+
+    #[test]
+    fn ctrl_keys_are_not_typed_into_inputs() {
+        let mut app = get_app_state();
+        app.mode = AppMode::Search;
+        app.search_query.clear();
+        app.handle_input(KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL));
+        app.handle_input(KeyEvent::new(KeyCode::Char('A'), KeyModifiers::SHIFT));
+        assert_eq!("A", app.search_query.value());
+    }
+
+    #[test]
+    fn ctrl_q_quits_from_any_mode() {
+        for mode in [AppMode::Logs, AppMode::Search, AppMode::Rename] {
+            let mut app = get_app_state();
+            app.mode = mode;
+            app.handle_input(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::CONTROL));
+            assert!(!app.running);
+        }
+        let mut app = get_app_state();
+        app.handle_input(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+        assert!(!app.running);
+    }
+
+    #[test]
+    fn events_are_bounded() {
+        let mut app = get_app_state();
+        for i in 0..MAX_EVENTS + 5 {
+            app.push_event(i as i64, format!("event {i}"));
+        }
+        assert_eq!(MAX_EVENTS, app.events.len());
+        assert_eq!(Some(&(5, "event 5".to_string())), app.events.front());
+
+        app.handle_input(KeyCode::Char('E'));
+        assert_eq!(AppMode::Events, app.mode);
+        app.handle_input(KeyCode::Esc);
+        assert_eq!(AppMode::Normal, app.mode);
+    }
+
+    #[test]
+    fn preview_follows_selection() {
+        let mut app = get_app_state();
+        assert_eq!(None, app.preview_target());
+        app.handle_input(KeyCode::Char('v'));
+        assert_eq!(Some("id1".to_string()), app.preview_target());
+        app.preview_for = Some("id1".to_string());
+        assert_eq!(None, app.preview_target());
+        app.handle_input(KeyCode::Down);
+        assert_eq!(Some("id2".to_string()), app.preview_target());
+    }
+
+    #[test]
+    fn reopened_logs_count_lines_since_last_view() {
+        let mut app = get_app_state();
+        app.mode = AppMode::Logs;
+        app.logs = vec!["a", "b", "c", "d"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        app.handle_input(KeyCode::Esc);
+        assert_eq!(
+            Some(&vec!["b".to_string(), "c".to_string(), "d".to_string()]),
+            app.seen_logs.get("id1")
+        );
+
+        app.handle_input(KeyCode::Enter);
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(None, app.unread_from);
+        app.logs = vec!["b", "c", "d", "e", "f"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        app.locate_unread();
+        assert_eq!(Some(3), app.unread_from);
+        assert_eq!(2, app.unread_logs());
+        assert!(app.seen_logs.is_empty());
+    }
+
+    #[test]
+    fn capital_d_toggles_the_summary_and_its_sampling() {
+        let mut app = get_app_state();
+        assert!(!app.samples_usage());
+        app.handle_input(KeyCode::Char('D'));
+        assert!(app.show_summary);
+        assert!(app.samples_usage());
+        app.handle_input(KeyCode::Char('D'));
+        assert!(!app.show_summary);
+    }
+
+    #[test]
+    fn w_toggles_wrapping_and_disables_sideways_scrolling() {
+        let mut app = get_app_state();
+        app.mode = AppMode::Logs;
+        app.handle_input(KeyCode::Char('l'));
+        assert_eq!(10, app.horizontal_scroll);
+
+        app.handle_input(KeyCode::Char('w'));
+        assert!(app.wrap_logs);
+        assert_eq!(0, app.horizontal_scroll);
+        app.handle_input(KeyCode::Char('l'));
+        assert_eq!(0, app.horizontal_scroll);
+
+        app.handle_input(KeyCode::Char('w'));
+        app.handle_input(KeyCode::Right);
+        assert_eq!(10, app.horizontal_scroll);
+    }
+
+    #[test]
+    fn capital_p_toggles_pause() {
+        let mut app = get_app_state();
+        app.handle_input(KeyCode::Char('P'));
+        assert!(app.paused);
+        app.mode = AppMode::Logs;
+        app.handle_input(KeyCode::Char('P'));
+        assert!(!app.paused);
+    }
+
+    #[test]
+    fn labels_view_scrolls_within_labels() {
+        let mut app = get_app_state();
+        app.container_data[0].labels = [("a", "1"), ("b", "2")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        app.vertical_scroll = 7;
+        app.mode = AppMode::ContextMenu;
+        app.menu_selected = 3;
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(AppMode::Labels, app.mode);
+        assert_eq!(0, app.vertical_scroll);
+
+        app.handle_input(KeyCode::Down);
+        app.handle_input(KeyCode::Down);
+        assert_eq!(1, app.vertical_scroll);
+        app.handle_input(KeyCode::Up);
+        assert_eq!(0, app.vertical_scroll);
+
+        app.handle_input(KeyCode::Esc);
+        assert_eq!(AppMode::Normal, app.mode);
+    }
+
+    #[test]
+    fn rename_prompt_requests_valid_rename() {
+        let mut app = get_app_state();
+        app.mode = AppMode::ContextMenu;
+        app.menu_selected = 4;
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(AppMode::Rename, app.mode);
+        assert_eq!("name1", app.rename_input.value());
+
+        app.handle_input(KeyCode::Backspace);
+        app.handle_input(KeyCode::Char('q'));
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(AppMode::Normal, app.mode);
+        assert_eq!(
+            Some(("id1".to_string(), "nameq".to_string())),
+            app.pending_rename
+        );
+    }
+
+    #[test]
+    fn remove_requires_typing_the_container_name() {
+        let mut app = get_app_state();
+        app.mode = AppMode::ContextMenu;
+        app.menu_selected = 6;
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(AppMode::ConfirmRemove, app.mode);
+
+        for c in "name2".chars() {
+            app.handle_input(KeyCode::Char(c));
+        }
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(AppMode::ConfirmRemove, app.mode);
+        assert_eq!(None, app.pending_remove);
+        assert!(app.prompt_error.is_some());
+
+        app.confirm_input.set("name1".to_string());
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(AppMode::Normal, app.mode);
+        assert_eq!(Some("id1".to_string()), app.pending_remove);
+        assert!(app.confirm_input.is_empty());
+    }
+
+    #[test]
+    fn remove_accepts_yes_and_can_be_cancelled() {
+        let mut app = get_app_state();
+        app.mode = AppMode::ConfirmRemove;
+        app.confirm_input.set("q".to_string());
+        app.handle_input(KeyCode::Esc);
+        assert_eq!(AppMode::Normal, app.mode);
+        assert!(app.confirm_input.is_empty());
+
+        app.mode = AppMode::ConfirmRemove;
+        app.confirm_input.set("yes".to_string());
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(Some("id1".to_string()), app.pending_remove);
+    }
+
+    #[test]
+    fn restart_asks_for_the_stop_timeout() {
+        let mut app = get_app_state();
+        app.config.stop_timeout_secs = 5;
+        app.mode = AppMode::ContextMenu;
+        app.handle_input(KeyCode::Char('r'));
+        assert_eq!(AppMode::ConfirmAction, app.mode);
+        assert_eq!("5", app.timeout_input.value());
+
+        app.handle_input(KeyCode::Backspace);
+        app.handle_input(KeyCode::Char('x'));
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(AppMode::ConfirmAction, app.mode);
+        assert!(app.prompt_error.is_some());
+
+        app.timeout_input.set("2");
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(AppMode::Normal, app.mode);
+        assert_eq!(
+            Some((ContainerAction::Restart, vec!["id1".to_string()], 2)),
+            app.pending_action
+        );
+    }
+
+    #[test]
+    fn restart_project_targets_every_container_of_the_project() {
+        let mut app = get_app_state();
+        app.container_data.push(ContainerInfo {
+            id: "id3".into(),
+            ..Default::default()
+        });
+        for i in [0, 2] {
+            app.container_data[i]
+                .labels
+                .insert(COMPOSE_PROJECT_LABEL.into(), "shop".into());
+        }
+        app.mode = AppMode::ContextMenu;
+        app.handle_input(KeyCode::Char('R'));
+        assert_eq!(AppMode::ConfirmAction, app.mode);
+        assert_eq!(vec!["id1", "id3"], app.action_targets);
+
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(
+            Some((
+                ContainerAction::Restart,
+                vec!["id1".to_string(), "id3".to_string()],
+                10
+            )),
+            app.pending_action
+        );
+    }
+
+    #[test]
+    fn restart_project_needs_a_compose_container() {
+        let mut app = get_app_state();
+        app.mode = AppMode::ContextMenu;
+        app.handle_input(KeyCode::Char('R'));
+        assert_eq!(AppMode::Normal, app.mode);
+        assert!(app.notification.is_some());
+    }
+
+    #[test]
+    fn watched_containers_are_announced_once_they_exit() {
+        let mut app = get_app_state();
+        app.config.watch_bell = true;
+        app.mode = AppMode::ContextMenu;
+        app.handle_input(KeyCode::Char('w'));
+        assert_eq!(AppMode::Normal, app.mode);
+        assert!(app.watched.contains("id1"));
+
+        let mut exited = app.container_data.clone();
+        exited[0].state = "exited".into();
+        exited[0].status = "Exited (0) 1 second ago".into();
+        app.set_container_data(exited.clone());
+        assert_eq!(
+            Some("WATCH: name1 exited: Exited (0) 1 second ago".to_string()),
+            app.notification
+        );
+        assert!(app.ring_bell);
+        assert!(app.watched.is_empty());
+
+        app.notification = None;
+        app.set_container_data(exited);
+        assert_eq!(None, app.notification);
+    }
+
+    #[test]
+    fn crashes_alert_once_per_cooldown() {
+        let mut app = get_app_state();
+        let running = app.container_data.clone();
+        let mut crashed = running.clone();
+        crashed[0].state = "exited".into();
+        crashed[0].status = "Exited (137) 1 second ago".into();
+
+        app.set_container_data(crashed.clone());
+        assert_eq!(None, app.notification);
+
+        app.set_container_data(running.clone());
+        app.config.crash_alert = CrashAlert::Bell;
+        app.set_container_data(crashed.clone());
+        assert_eq!(
+            Some("CRASH: name1 crashed (137)".to_string()),
+            app.notification
+        );
+        assert!(app.ring_bell);
+
+        // Restarting and crashing again within the cooldown stays quiet.
+        app.notification = None;
+        app.ring_bell = false;
+        app.set_container_data(running.clone());
+        app.set_container_data(crashed.clone());
+        assert_eq!(None, app.notification);
+        assert!(!app.ring_bell);
+
+        app.config.crash_alert = CrashAlert::Desktop;
+        app.config.crash_alert_cooldown_secs = 0;
+        app.set_container_data(running);
+        app.set_container_data(crashed);
+        assert_eq!(Some("name1 crashed (137)".to_string()), app.desktop_alert);
+    }
+
+    #[test]
+    fn clean_exits_dont_alert() {
+        let mut app = get_app_state();
+        app.config.crash_alert = CrashAlert::Bell;
+        let mut exited = app.container_data.clone();
+        exited[0].state = "exited".into();
+        exited[0].status = "Exited (0) 1 second ago".into();
+        app.set_container_data(exited);
+        assert_eq!(None, app.notification);
+        assert!(!app.ring_bell);
+    }
+
+    #[test]
+    fn watching_toggles_and_needs_a_running_container() {
+        let mut app = get_app_state();
+        app.toggle_watch();
+        app.toggle_watch();
+        assert!(app.watched.is_empty());
+        assert_eq!(Some("Stopped watching name1".to_string()), app.notification);
+
+        app.selected = 1;
+        app.toggle_watch();
+        assert!(app.watched.is_empty());
+        assert_eq!(Some("name2 isn't running".to_string()), app.notification);
+    }
+
+    #[test]
+    fn unconfirmed_actions_run_right_away() {
+        let mut app = get_app_state();
+        app.config.confirm.stop = false;
+        app.config.confirm.remove = false;
+        app.mode = AppMode::ContextMenu;
+        app.handle_input(KeyCode::Char('t'));
+        assert_eq!(AppMode::Normal, app.mode);
+        assert_eq!(
+            Some((ContainerAction::Stop, vec!["id1".to_string()], 10)),
+            app.pending_action
+        );
+
+        app.mode = AppMode::ContextMenu;
+        app.handle_input(KeyCode::Char('x'));
+        assert_eq!(AppMode::Normal, app.mode);
+        assert_eq!(Some("id1".to_string()), app.pending_remove);
+
+        app.mode = AppMode::ContextMenu;
+        app.handle_input(KeyCode::Char('r'));
+        assert_eq!(AppMode::ConfirmAction, app.mode);
+    }
+
+    #[test]
+    fn stop_can_be_cancelled() {
+        let mut app = get_app_state();
+        app.mode = AppMode::ContextMenu;
+        app.handle_input(KeyCode::Char('t'));
+        assert_eq!(Some(ContainerAction::Stop), app.confirm_action);
+        app.handle_input(KeyCode::Esc);
+        assert_eq!(AppMode::Normal, app.mode);
+        assert_eq!(None, app.pending_action);
+    }
+
+    #[test]
+    fn rename_prompt_rejects_invalid_name() {
+        let mut app = get_app_state();
+        app.mode = AppMode::Rename;
+        app.rename_input = "bad name".into();
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(AppMode::Rename, app.mode);
+        assert_eq!(None, app.pending_rename);
+        assert!(app.prompt_error.is_some());
+
+        app.handle_input(KeyCode::Esc);
+        assert_eq!(AppMode::Normal, app.mode);
+        assert!(app.rename_input.is_empty());
+    }
+
+    #[test]
+    fn slash_opens_search() {
+        let mut app = get_app_state();
+        app.mode = AppMode::Logs;
+        app.handle_input(KeyCode::Char('/'));
+        app.handle_input(KeyCode::Char('a'));
+        assert_eq!(AppMode::Search, app.mode);
+        assert_eq!("a", app.search_query.value());
+    }
+
+    #[test]
+    fn jumps_to_next_match_logs_mode() {
+        let mut app = get_app_state();
+        app.mode = AppMode::Logs;
+        app.search_matches = vec![1, 2];
+        app.current_match_index = Some(0);
+        app.handle_input(KeyCode::Char('n'));
+        assert_eq!(Some(1), app.current_match_index);
+        assert_eq!(2, app.vertical_scroll);
+    }
+
+    #[test]
+    fn jumps_to_previous_match_logs_mode() {
+        let mut app = get_app_state();
+        app.mode = AppMode::Logs;
+        app.search_matches = vec![1, 2];
         app.current_match_index = Some(1);
         app.handle_input(KeyCode::Char('N'));
         assert_eq!(Some(0), app.current_match_index);
+        assert_eq!(1, app.vertical_scroll);
+    }
+
+    #[test]
+    fn jumping_to_a_match_centers_it() {
+        let mut app = get_app_state();
+        app.mode = AppMode::Logs;
+        app.visible_height = 10;
+        app.search_matches = vec![3, 20, 48];
+        app.current_match_index = Some(0);
+        app.handle_input(KeyCode::Char('n'));
+        assert_eq!(15, app.vertical_scroll);
+        // Clamped so the view never runs past either end of the buffer.
+        app.handle_input(KeyCode::Char('n'));
+        assert_eq!(40, app.vertical_scroll);
+        app.handle_input(KeyCode::Char('n'));
+        assert_eq!(0, app.vertical_scroll);
+    }
+
+    #[test]
+    fn matches_only_jump_in_logs_mode() {
+        let mut app = get_app_state();
+        app.mode = AppMode::Normal;
+        app.search_matches = vec![48, 49];
+        app.current_match_index = Some(0);
+        app.handle_input(KeyCode::Char('n'));
+        app.handle_input(KeyCode::Char('N'));
+        assert_eq!(Some(0), app.current_match_index);
+        assert_eq!(0, app.selected);
+    }
+
+    #[test]
+    fn closing_logs_clears_the_log_search() {
+        let mut app = get_app_state();
+        app.mode = AppMode::Logs;
+        app.search_query.set("log");
+        app.search_matches = vec![48, 49];
+        app.current_match_index = Some(0);
+        app.handle_input(KeyCode::Esc);
+        assert_eq!(AppMode::Normal, app.mode);
+        assert!(app.search_matches.is_empty());
+        assert_eq!(None, app.current_match_index);
+        assert!(app.search_query.is_empty());
+    }
+
+    #[test]
+    fn p_requests_pager_in_logs_mode() {
+        let mut app = get_app_state();
+        app.handle_input(KeyCode::Char('p'));
+        assert!(!app.open_pager);
+
+        app.mode = AppMode::Logs;
+        app.handle_input(KeyCode::Char('p'));
+        assert!(app.open_pager);
+    }
+
+    #[test]
+    fn e_requests_log_export_in_logs_mode() {
+        let mut app = get_app_state();
+        app.handle_input(KeyCode::Char('e'));
+        assert!(!app.export_logs);
+
+        app.mode = AppMode::Logs;
+        app.handle_input(KeyCode::Char('e'));
+        assert!(app.export_logs);
+    }
+
+    #[test]
+    fn d_toggles_duplicate_collapsing_in_logs_mode() {
+        let mut app = get_app_state();
+        app.mode = AppMode::Logs;
+        app.handle_input(KeyCode::Char('d'));
+        assert!(app.collapse_duplicates);
+        app.handle_input(KeyCode::Char('d'));
+        assert!(!app.collapse_duplicates);
+    }
+
+    #[test]
+    fn a_toggles_ansi_colors() {
+        let mut app = get_app_state();
+        app.mode = AppMode::Logs;
+        app.handle_input(KeyCode::Char('a'));
+        assert!(app.ansi_colors);
+        app.handle_input(KeyCode::Char('a'));
+        assert!(!app.ansi_colors);
+    }
+
+    #[test]
+    fn search_ignores_ansi_escapes() {
+        let mut app = get_app_state();
+        app.logs = vec!["\x1b[31merr\x1b[0mor".to_string(), "fine".to_string()];
+        app.last_mode = AppMode::Logs;
+        app.mode = AppMode::Search;
+        app.search_query = "error".into();
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(vec![0], app.search_matches);
+    }
+
+    #[test]
+    fn capital_j_toggles_structured_logs() {
+        let mut app = get_app_state();
+        app.mode = AppMode::Logs;
+        app.handle_input(KeyCode::Char('J'));
+        assert!(app.structured_logs);
+        assert_eq!(0, app.vertical_scroll);
+    }
+
+    #[test]
+    fn c_clears_search_highlighting() {
+        let mut app = get_app_state();
+        app.mode = AppMode::Logs;
+        app.search_query = "log".into();
+        app.search_matches = vec![1, 2];
+        app.current_match_index = Some(1);
+        app.handle_input(KeyCode::Char('c'));
+        assert_eq!(AppMode::Logs, app.mode);
+        assert!(app.search_query.is_empty());
+        assert!(app.search_matches.is_empty());
+        assert_eq!(None, app.current_match_index);
+    }
+
+    #[test]
+    fn container_list_clamps_by_default() {
+        let mut app = get_app_state();
+        app.selected = 1;
+        app.handle_input(KeyCode::Down);
+        assert_eq!(1, app.selected);
+        app.selected = 0;
+        app.handle_input(KeyCode::Up);
+        assert_eq!(0, app.selected);
+    }
+
+    #[test]
+    fn container_list_wraps_when_configured() {
+        let mut app = get_app_state();
+        app.config.wrap_container_list = true;
+        app.selected = 1;
+        app.handle_input(KeyCode::Down);
+        assert_eq!(0, app.selected);
+        app.handle_input(KeyCode::Up);
+        assert_eq!(1, app.selected);
+    }
+
+    #[test]
+    fn menu_clamps_when_wrapping_disabled() {
+        let mut app = get_app_state();
+        app.config.wrap_menu = false;
+        app.mode = AppMode::ContextMenu;
+        app.handle_input(KeyCode::Up);
+        assert_eq!(0, app.menu_selected);
+        app.menu_selected = app.menu_items.len() - 1;
+        app.handle_input(KeyCode::Down);
+        assert_eq!(app.menu_items.len() - 1, app.menu_selected);
+    }
+
+    #[test]
+    fn plus_and_minus_resize_logs_overlay() {
+        let mut app = get_app_state();
+        app.mode = AppMode::Logs;
+        app.handle_input(KeyCode::Char('+'));
+        assert_eq!(85, app.config.overlay.width);
+        app.handle_input(KeyCode::Char('-'));
+        app.handle_input(KeyCode::Char('-'));
+        assert_eq!(75, app.config.overlay.height);
+
+        app.mode = AppMode::Normal;
+        app.handle_input(KeyCode::Char('+'));
+        assert_eq!(75, app.config.overlay.width);
     }
 
     #[test]
@@ -0,0 +1,58 @@
+use clap::Parser;
+
+#[derive(Parser, Debug, Default)]
+#[command(name = "tugboat", version, about = "Docker TUI")]
+pub struct Cli {
+    /// Only show containers matching this label selector (e.g. `app=web`)
+    #[arg(short, long, value_name = "KEY[=VALUE]")]
+    pub label: Option<String>,
+
+    /// Record more of tugboat's own activity in the diagnostics view (`!`);
+    /// repeat for more detail
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Print the logs of this container (id or name) and exit, without the UI
+    #[arg(long, value_name = "CONTAINER")]
+    pub logs: Option<String>,
+
+    /// Open straight into the resource graphs of this container (id or name);
+    /// closing them quits
+    #[arg(long, value_name = "CONTAINER", conflicts_with = "logs")]
+    pub stats: Option<String>,
+
+    /// Number of most recent log lines `--logs` prints
+    #[arg(long, value_name = "N", default_value_t = 100, requires = "logs")]
+    pub tail: usize,
+
+    /// Keep the escape sequences (e.g. colors) in `--logs` output
+    #[arg(long, requires = "logs")]
+    pub raw: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn tail_and_raw_need_logs() {
+        let cli = Cli::try_parse_from(["tugboat", "--logs", "web", "--tail", "5"]).unwrap();
+        assert_eq!(Some("web".to_string()), cli.logs);
+        assert_eq!(5, cli.tail);
+        assert!(!cli.raw);
+        assert!(Cli::try_parse_from(["tugboat", "--raw"]).is_err());
+    }
+
+    #[test]
+    fn stats_and_logs_are_exclusive() {
+        let cli = Cli::try_parse_from(["tugboat", "--stats", "web"]).unwrap();
+        assert_eq!(Some("web".to_string()), cli.stats);
+        assert!(Cli::try_parse_from(["tugboat", "--stats", "web", "--logs", "db"]).is_err());
+    }
+}
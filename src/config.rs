@@ -0,0 +1,635 @@
+use std::{error::Error, fs, path::PathBuf};
+
+use ratatui::{style::Color, symbols::Marker};
+use serde::{Deserialize, Deserializer};
+use smart_default::SmartDefault;
+
+use crate::keybindings::reserved_keys;
+
+const MIN_OVERLAY_PERCENT: u16 = 20;
+const MAX_OVERLAY_PERCENT: u16 = 100;
+
+/// Size of a centered overlay as a percentage of the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct OverlaySize {
+    #[serde(deserialize_with = "overlay_percent")]
+    pub width: u16,
+    #[serde(deserialize_with = "overlay_percent")]
+    pub height: u16,
+}
+
+/// Reads a percentage clamped like `resize` does, so the config can't size an
+/// overlay beyond the terminal.
+fn overlay_percent<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u16, D::Error> {
+    u16::deserialize(deserializer)
+        .map(|percent| percent.clamp(MIN_OVERLAY_PERCENT, MAX_OVERLAY_PERCENT))
+}
+
+impl OverlaySize {
+    pub fn resize(&mut self, delta: i16) {
+        let resize = |percent: u16| {
+            percent
+                .saturating_add_signed(delta)
+                .clamp(MIN_OVERLAY_PERCENT, MAX_OVERLAY_PERCENT)
+        };
+        self.width = resize(self.width);
+        self.height = resize(self.height);
+    }
+}
+
+/// What Enter does on the container list.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EnterAction {
+    #[default]
+    Menu,
+    Logs,
+    Details,
+}
+
+/// Border color of each overlay, so the current mode is recognizable at a glance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, SmartDefault)]
+#[serde(default)]
+pub struct BorderColors {
+    #[default(Color::Cyan)]
+    pub logs: Color,
+    #[default(Color::Green)]
+    pub resources: Color,
+    #[default(Color::Blue)]
+    pub details: Color,
+    #[default(Color::Magenta)]
+    pub labels: Color,
+    #[default(Color::Yellow)]
+    pub events: Color,
+    #[default(Color::LightRed)]
+    pub diagnostics: Color,
+    #[default(Color::White)]
+    pub menu: Color,
+}
+
+/// Where the logs overlay starts when opened.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogAnchor {
+    /// Follow the newest lines as they arrive.
+    #[default]
+    Bottom,
+    /// Start at the oldest line and stay there.
+    Top,
+}
+
+/// Log lines kept out of the logs overlay. Patterns match as plain substrings
+/// of the line without color codes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct LogFilter {
+    /// If set, only lines containing one of these are shown.
+    pub include: Vec<String>,
+    /// Lines containing any of these are hidden, e.g. health-check polling.
+    pub exclude: Vec<String>,
+}
+
+impl LogFilter {
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    pub fn keeps(&self, line: &str) -> bool {
+        let contains = |pattern: &String| line.contains(pattern.as_str());
+        (self.include.is_empty() || self.include.iter().any(contains))
+            && !self.exclude.iter().any(contains)
+    }
+}
+
+/// A user-defined command run against the selected container, from the menu
+/// or with its key on the container list.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct CustomAction {
+    pub label: String,
+    pub key: char,
+    /// Run with `sh -c`; `{id}` and `{name}` stand for the container's.
+    pub command: String,
+    /// Show the output in a notification instead of handing over the terminal.
+    #[serde(default)]
+    pub capture: bool,
+}
+
+impl CustomAction {
+    pub fn command_for(&self, id: &str, name: &str) -> String {
+        self.command.replace("{id}", id).replace("{name}", name)
+    }
+}
+
+/// Which container actions ask before they run. Removing can't be undone, so
+/// it is best left on; stopping and restarting are easy to reverse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, SmartDefault)]
+#[serde(default)]
+pub struct Confirmations {
+    #[default = true]
+    pub stop: bool,
+    #[default = true]
+    pub restart: bool,
+    /// Restarting every container of a compose project at once.
+    #[default = true]
+    pub restart_project: bool,
+    #[default = true]
+    pub remove: bool,
+}
+
+/// A metric graphed in Resources mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Metric {
+    Cpu,
+    Mem,
+}
+
+/// Order of the container list.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContainerSort {
+    #[default]
+    Name,
+    /// Most recently created first.
+    Newest,
+    Oldest,
+}
+
+impl ContainerSort {
+    pub fn next(self) -> ContainerSort {
+        match self {
+            ContainerSort::Name => ContainerSort::Newest,
+            ContainerSort::Newest => ContainerSort::Oldest,
+            ContainerSort::Oldest => ContainerSort::Name,
+        }
+    }
+}
+
+/// Time zone that times are shown in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeZone {
+    #[default]
+    Local,
+    Utc,
+}
+
+/// How to call attention to a container that exits with a non-zero code.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CrashAlert {
+    #[default]
+    Off,
+    /// The terminal bell.
+    Bell,
+    /// A desktop notification through the terminal (OSC 9), which terminals
+    /// without support ignore.
+    Desktop,
+}
+
+/// How CPU % relates to the host's cores.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CpuScale {
+    /// 100% per busy core, like `docker stats`, so it can exceed 100.
+    #[default]
+    Aggregate,
+    /// Divided by the host's cores to stay within 0-100, like most system
+    /// monitors' totals.
+    Normalized,
+}
+
+impl CpuScale {
+    pub fn toggled(self) -> CpuScale {
+        match self {
+            CpuScale::Aggregate => CpuScale::Normalized,
+            CpuScale::Normalized => CpuScale::Aggregate,
+        }
+    }
+}
+
+/// Colors and markers of the resource graphs and memory gauges.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphPalette {
+    #[default]
+    Default,
+    /// Okabe-Ito colors, which stay apart for the common color vision
+    /// deficiencies, plus a different marker per metric so no graph relies on
+    /// color alone.
+    Colorblind,
+}
+
+const OKABE_ITO_BLUE: Color = Color::Rgb(0, 114, 178);
+const OKABE_ITO_ORANGE: Color = Color::Rgb(230, 159, 0);
+const OKABE_ITO_VERMILLION: Color = Color::Rgb(213, 94, 0);
+
+impl GraphPalette {
+    /// Line color and marker of the graph of `metric`.
+    pub fn graph_style(self, metric: Metric) -> (Color, Marker) {
+        match (self, metric) {
+            (GraphPalette::Default, _) => (Color::Cyan, Marker::Braille),
+            (GraphPalette::Colorblind, Metric::Cpu) => (OKABE_ITO_BLUE, Marker::Braille),
+            (GraphPalette::Colorblind, Metric::Mem) => (OKABE_ITO_ORANGE, Marker::Dot),
+        }
+    }
+
+    /// Fill color of a gauge at `ratio`: fine, getting full, nearly full.
+    pub fn level_color(self, ratio: f64) -> Color {
+        let (low, mid, high) = match self {
+            GraphPalette::Default => (Color::Green, Color::Yellow, Color::Red),
+            GraphPalette::Colorblind => (OKABE_ITO_BLUE, OKABE_ITO_ORANGE, OKABE_ITO_VERMILLION),
+        };
+        if ratio >= 0.9 {
+            high
+        } else if ratio >= 0.7 {
+            mid
+        } else {
+            low
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, SmartDefault)]
+#[serde(default)]
+pub struct Config {
+    /// Wrap from the last container back to the first (and vice versa).
+    #[default = false]
+    pub wrap_container_list: bool,
+    /// Wrap around at the ends of the context menu.
+    #[default = true]
+    pub wrap_menu: bool,
+    /// Size of the logs, resources and details overlays.
+    #[default(_code = "OverlaySize { width: 80, height: 80 }")]
+    pub overlay: OverlaySize,
+    /// Size of the context menu.
+    #[default(_code = "OverlaySize { width: 30, height: 20 }")]
+    pub menu: OverlaySize,
+    /// How long to wait for input before redrawing, in milliseconds. Longer
+    /// timeouts save wakeups but make animations and notifications coarser.
+    #[default = 200]
+    pub poll_timeout_ms: u64,
+    /// Order of the container list; `o` cycles through the orders.
+    pub sort: ContainerSort,
+    /// `strftime`-style format of creation times.
+    #[default = "%Y-%m-%d %H:%M"]
+    pub created_format: String,
+    /// `strftime`-style format of event and diagnostic times.
+    #[default = "%H:%M:%S"]
+    pub time_format: String,
+    /// Whether times are shown in local time or UTC.
+    pub time_zone: TimeZone,
+    /// Action for Enter on the container list: open the menu, logs or details.
+    pub enter_action: EnterAction,
+    /// Scrolling down to within this many lines of the newest log line resumes
+    /// following the stream.
+    #[default = 2]
+    pub follow_threshold: usize,
+    /// Whether the logs overlay opens following the newest lines or at the oldest.
+    pub log_anchor: LogAnchor,
+    /// Whether reconnecting the log stream (`r`) starts over instead of
+    /// appending to the lines already shown.
+    #[default = false]
+    pub reconnect_clears_logs: bool,
+    /// Log lines longer than this many widths of the logs view are cut short
+    /// with a note of how much is hidden, until `x` expands them; 0 never cuts.
+    #[default = 4]
+    pub long_line_screens: usize,
+    /// How long quitting waits for running actions such as log exports, in
+    /// milliseconds. Streams are always stopped right away.
+    #[default = 2000]
+    pub shutdown_grace_ms: u64,
+    /// Seconds Docker waits after SIGTERM before killing a container being
+    /// stopped or restarted; the confirmation prompt starts with this value.
+    #[default = 10]
+    pub stop_timeout_secs: u32,
+    /// How many log streams keep running after their overlay closes, so
+    /// switching back to those containers is instant and keeps their history.
+    #[default = 0]
+    pub background_log_streams: usize,
+    /// Overlay border colors, by mode.
+    pub borders: BorderColors,
+    /// Noise to hide from streamed logs; `f` in the logs view turns it off and on.
+    pub log_filter: LogFilter,
+    /// Seconds of stats averaged into each point of the resource graphs, so the
+    /// 60-point window spans 60 times this.
+    #[default = 1]
+    pub stats_interval_secs: usize,
+    /// Metrics graphed in Resources mode, top to bottom.
+    #[default(_code = "vec![Metric::Cpu, Metric::Mem]")]
+    pub stats: Vec<Metric>,
+    /// Whether the CPU graph counts 100% per core or is normalized to the
+    /// whole host; `u` in the resources view switches it.
+    pub cpu_scale: CpuScale,
+    /// Colors of the resource graphs and memory gauges.
+    pub graph_palette: GraphPalette,
+    /// Ring the terminal bell when a watched container exits.
+    #[default = false]
+    pub watch_bell: bool,
+    /// Alert when any container exits with a non-zero code.
+    pub crash_alert: CrashAlert,
+    /// Seconds before the same container can alert again, so a crash loop
+    /// alerts once rather than on every restart.
+    #[default = 300]
+    pub crash_alert_cooldown_secs: u64,
+    /// Commands listed after the built-in menu actions.
+    pub actions: Vec<CustomAction>,
+    /// Actions that ask before they run; unconfirmed stops and restarts use
+    /// `stop_timeout_secs`.
+    pub confirm: Confirmations,
+}
+
+impl Config {
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("tugboat").join("config.toml"))
+    }
+
+    /// Loads the config file, falling back to defaults if it doesn't exist.
+    pub fn load() -> Result<Self, Box<dyn Error>> {
+        match Self::path() {
+            Some(path) if path.exists() => Self::parse(&fs::read_to_string(&path)?)
+                .map_err(|e| e.to_string())
+                .and_then(|config| config.validate().map(|()| config))
+                .map_err(|e| format!("Invalid config file {}: {}", path.display(), e).into()),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// Rejects what parses but can't work, like a custom action key that a
+    /// built-in binding would always take first.
+    pub fn validate(&self) -> Result<(), String> {
+        let reserved = reserved_keys();
+        match self
+            .actions
+            .iter()
+            .find(|action| reserved.contains(&action.key))
+        {
+            Some(action) => Err(format!(
+                "key {:?} of action {:?} is taken by a built-in binding",
+                action.key, action.label
+            )),
+            None => Ok(()),
+        }
+    }
+
+    pub fn parse(contents: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_config_uses_defaults() {
+        let config = Config::parse("").unwrap();
+        assert_eq!(Config::default(), config);
+        assert!(!config.wrap_container_list);
+        assert!(config.wrap_menu);
+    }
+
+    #[test]
+    fn parses_wrap_settings() {
+        let config = Config::parse("wrap_container_list = true\nwrap_menu = false").unwrap();
+        assert!(config.wrap_container_list);
+        assert!(!config.wrap_menu);
+    }
+
+    #[test]
+    fn parses_overlay_sizes() {
+        let config = Config::parse("overlay = { width = 95, height = 90 }").unwrap();
+        assert_eq!(
+            OverlaySize {
+                width: 95,
+                height: 90
+            },
+            config.overlay
+        );
+        assert_eq!(Config::default().menu, config.menu);
+    }
+
+    #[test]
+    fn clamps_overlay_sizes() {
+        let config = Config::parse(
+            "overlay = { width = 150, height = 90 }\nmenu = { width = 0, height = 20 }",
+        )
+        .unwrap();
+        assert_eq!(100, config.overlay.width);
+        assert_eq!(90, config.overlay.height);
+        assert_eq!(20, config.menu.width);
+        assert!(Config::parse("overlay = { width = -10, height = 90 }").is_err());
+    }
+
+    #[test]
+    fn parses_poll_timeout() {
+        assert_eq!(200, Config::default().poll_timeout_ms);
+        let config = Config::parse("poll_timeout_ms = 1000").unwrap();
+        assert_eq!(1000, config.poll_timeout_ms);
+    }
+
+    #[test]
+    fn parses_enter_action() {
+        assert_eq!(EnterAction::Menu, Config::default().enter_action);
+        let config = Config::parse("enter_action = \"logs\"").unwrap();
+        assert_eq!(EnterAction::Logs, config.enter_action);
+        assert!(Config::parse("enter_action = \"shell\"").is_err());
+    }
+
+    #[test]
+    fn overlay_resize_is_clamped() {
+        let mut size = OverlaySize {
+            width: 90,
+            height: 25,
+        };
+        size.resize(20);
+        assert_eq!(100, size.width);
+        size.resize(-80);
+        assert_eq!(20, size.width);
+        assert_eq!(20, size.height);
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        assert!(Config::parse("wrap_menu = \"sometimes\"").is_err());
+    }
+
+    #[test]
+    fn parses_stats() {
+        assert_eq!(vec![Metric::Cpu, Metric::Mem], Config::default().stats);
+        let config = Config::parse("stats = [\"mem\"]").unwrap();
+        assert_eq!(vec![Metric::Mem], config.stats);
+        assert!(Config::parse("stats = [\"disk\"]").is_err());
+    }
+
+    #[test]
+    fn parses_follow_threshold() {
+        assert_eq!(2, Config::default().follow_threshold);
+        let config = Config::parse("follow_threshold = 0").unwrap();
+        assert_eq!(0, config.follow_threshold);
+    }
+
+    #[test]
+    fn parses_log_anchor() {
+        assert_eq!(LogAnchor::Bottom, Config::default().log_anchor);
+        let config = Config::parse("log_anchor = \"top\"").unwrap();
+        assert_eq!(LogAnchor::Top, config.log_anchor);
+    }
+
+    #[test]
+    fn parses_border_colors() {
+        let config = Config::parse("borders = { logs = \"red\", menu = \"#00ff00\" }").unwrap();
+        assert_eq!(Color::Red, config.borders.logs);
+        assert_eq!(Color::Rgb(0, 255, 0), config.borders.menu);
+        assert_eq!(Color::Green, config.borders.resources);
+        assert_ne!(config.borders.events, config.borders.diagnostics);
+        let config = Config::parse("borders = { diagnostics = \"lightred\" }").unwrap();
+        assert_eq!(Color::LightRed, config.borders.diagnostics);
+    }
+
+    #[test]
+    fn parses_log_filter() {
+        let config = Config::parse(
+            "[log_filter]\ninclude = [\"api\"]\nexclude = [\"GET /health\", \"DEBUG\"]",
+        )
+        .unwrap();
+        let filter = &config.log_filter;
+        assert!(filter.keeps("api: POST /orders 201"));
+        assert!(!filter.keeps("api: GET /health 200"));
+        assert!(!filter.keeps("worker: done"));
+        assert!(LogFilter::default().keeps("anything"));
+        assert!(LogFilter::default().is_empty());
+    }
+
+    #[test]
+    fn parses_stats_interval() {
+        assert_eq!(1, Config::default().stats_interval_secs);
+        let config = Config::parse("stats_interval_secs = 5").unwrap();
+        assert_eq!(5, config.stats_interval_secs);
+    }
+
+    #[test]
+    fn parses_background_log_streams() {
+        assert_eq!(0, Config::default().background_log_streams);
+        let config = Config::parse("background_log_streams = 3").unwrap();
+        assert_eq!(3, config.background_log_streams);
+    }
+
+    #[test]
+    fn parses_stop_timeout() {
+        assert_eq!(10, Config::default().stop_timeout_secs);
+        let config = Config::parse("stop_timeout_secs = 2").unwrap();
+        assert_eq!(2, config.stop_timeout_secs);
+    }
+
+    #[test]
+    fn parses_sort() {
+        assert_eq!(ContainerSort::Name, Config::default().sort);
+        let config = Config::parse("sort = \"newest\"").unwrap();
+        assert_eq!(ContainerSort::Newest, config.sort);
+        assert_eq!(ContainerSort::Oldest, config.sort.next());
+    }
+
+    #[test]
+    fn parses_time_settings() {
+        let config = Config::default();
+        assert_eq!("%H:%M:%S", config.time_format);
+        assert_eq!(TimeZone::Local, config.time_zone);
+        let config = Config::parse("time_format = \"%I:%M %p\"\ntime_zone = \"utc\"").unwrap();
+        assert_eq!("%I:%M %p", config.time_format);
+        assert_eq!(TimeZone::Utc, config.time_zone);
+    }
+
+    #[test]
+    fn parses_crash_alert() {
+        assert_eq!(CrashAlert::Off, Config::default().crash_alert);
+        let config = Config::parse("crash_alert = \"desktop\"").unwrap();
+        assert_eq!(CrashAlert::Desktop, config.crash_alert);
+        assert!(Config::parse("crash_alert = \"email\"").is_err());
+    }
+
+    #[test]
+    fn parses_cpu_scale() {
+        assert_eq!(CpuScale::Aggregate, Config::default().cpu_scale);
+        let config = Config::parse("cpu_scale = \"normalized\"").unwrap();
+        assert_eq!(CpuScale::Normalized, config.cpu_scale);
+        assert_eq!(CpuScale::Aggregate, config.cpu_scale.toggled());
+    }
+
+    #[test]
+    fn parses_graph_palette() {
+        assert_eq!(GraphPalette::Default, Config::default().graph_palette);
+        let config = Config::parse("graph_palette = \"colorblind\"").unwrap();
+        assert_eq!(GraphPalette::Colorblind, config.graph_palette);
+        let (cpu, cpu_marker) = config.graph_palette.graph_style(Metric::Cpu);
+        let (mem, mem_marker) = config.graph_palette.graph_style(Metric::Mem);
+        assert_ne!(cpu, mem);
+        assert_ne!(cpu_marker, mem_marker);
+    }
+
+    #[test]
+    fn parses_long_line_screens() {
+        assert_eq!(4, Config::default().long_line_screens);
+        let config = Config::parse("long_line_screens = 0").unwrap();
+        assert_eq!(0, config.long_line_screens);
+    }
+
+    #[test]
+    fn parses_custom_actions() {
+        let config = Config::parse(
+            r#"
+            [[actions]]
+            label = "Diagnose"
+            key = "g"
+            command = "docker exec {id} diagnose.sh --name {name}"
+
+            [[actions]]
+            label = "Disk usage"
+            key = "i"
+            command = "docker exec {id} df -h"
+            capture = true
+            "#,
+        )
+        .unwrap();
+        assert_eq!(2, config.actions.len());
+        assert!(!config.actions[0].capture);
+        assert!(config.actions[1].capture);
+        assert_eq!(
+            "docker exec abc diagnose.sh --name web",
+            config.actions[0].command_for("abc", "web")
+        );
+    }
+
+    #[test]
+    fn rejects_custom_action_keys_taken_by_built_ins() {
+        let config = |key: char| {
+            Config::parse(&format!(
+                "[[actions]]\nlabel = \"Tail\"\nkey = \"{key}\"\ncommand = \"true\""
+            ))
+            .unwrap()
+        };
+        assert_eq!(Ok(()), config('g').validate());
+        assert_eq!(
+            Err("key 'l' of action \"Tail\" is taken by a built-in binding".to_string()),
+            config('l').validate()
+        );
+        assert!(config('G').validate().is_err());
+    }
+
+    #[test]
+    fn parses_confirmations() {
+        assert_eq!(Confirmations::default(), Config::default().confirm);
+        assert!(Config::default().confirm.remove);
+        let config = Config::parse("[confirm]\nstop = false").unwrap();
+        assert!(!config.confirm.stop);
+        assert!(config.confirm.restart);
+        assert!(config.confirm.remove);
+    }
+
+    #[test]
+    fn parses_shutdown_grace() {
+        assert_eq!(2000, Config::default().shutdown_grace_ms);
+        let config = Config::parse("shutdown_grace_ms = 0").unwrap();
+        assert_eq!(0, config.shutdown_grace_ms);
+    }
+}
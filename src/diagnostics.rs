@@ -0,0 +1,136 @@
+use std::{collections::VecDeque, time::SystemTime};
+
+/// Number of diagnostics kept; older ones are dropped first.
+const MAX_DIAGNOSTICS: usize = 500;
+
+/// Severity of a diagnostic, from most to least important.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    #[default]
+    Warn,
+    Info,
+    Debug,
+}
+
+impl Level {
+    /// The most detailed level recorded for `--verbose` given `count` times;
+    /// errors and warnings are always kept.
+    pub fn from_verbosity(count: u8) -> Level {
+        match count {
+            0 => Level::Warn,
+            1 => Level::Info,
+            _ => Level::Debug,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub at: SystemTime,
+    pub level: Level,
+    pub message: String,
+}
+
+/// Tugboat's own warnings and errors, kept in memory because anything printed
+/// would end up in the middle of the UI.
+#[derive(Debug, Default)]
+pub struct Diagnostics {
+    /// The most detailed level recorded.
+    pub verbosity: Level,
+    entries: VecDeque<Diagnostic>,
+}
+
+impl Diagnostics {
+    pub fn new(verbosity: Level) -> Self {
+        Diagnostics {
+            verbosity,
+            ..Default::default()
+        }
+    }
+
+    pub fn record(&mut self, level: Level, message: impl Into<String>) {
+        if level > self.verbosity {
+            return;
+        }
+        if self.entries.len() == MAX_DIAGNOSTICS {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(Diagnostic {
+            at: SystemTime::now(),
+            level,
+            message: message.into(),
+        });
+    }
+
+    pub fn error(&mut self, message: impl Into<String>) {
+        self.record(Level::Error, message);
+    }
+
+    pub fn warn(&mut self, message: impl Into<String>) {
+        self.record(Level::Warn, message);
+    }
+
+    pub fn info(&mut self, message: impl Into<String>) {
+        self.record(Level::Info, message);
+    }
+
+    pub fn debug(&mut self, message: impl Into<String>) {
+        self.record(Level::Debug, message);
+    }
+
+    /// Recorded diagnostics, oldest first.
+    pub fn entries(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.entries.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_levels_above_the_verbosity() {
+        let mut diagnostics = Diagnostics::default();
+        diagnostics.error("connection refused");
+        diagnostics.info("opened log stream");
+        diagnostics.debug("refreshed 3 containers");
+        let recorded: Vec<_> = diagnostics
+            .entries()
+            .map(|diagnostic| (diagnostic.level, diagnostic.message.as_str()))
+            .collect();
+        assert_eq!(vec![(Level::Error, "connection refused")], recorded);
+
+        diagnostics = Diagnostics::new(Level::from_verbosity(2));
+        diagnostics.error("connection refused");
+        diagnostics.debug("refreshed 3 containers");
+        assert_eq!(2, diagnostics.entries().count());
+    }
+
+    #[test]
+    fn drops_the_oldest_beyond_the_limit() {
+        let mut diagnostics = Diagnostics::default();
+        for i in 0..=MAX_DIAGNOSTICS {
+            diagnostics.warn(i.to_string());
+        }
+        assert_eq!(MAX_DIAGNOSTICS, diagnostics.entries().count());
+        let oldest = diagnostics
+            .entries()
+            .next()
+            .map(|diagnostic| diagnostic.message.as_str());
+        assert_eq!(Some("1"), oldest);
+    }
+}
@@ -1,18 +1,237 @@
+use async_trait::async_trait;
 use bollard::Docker as BollardDocker;
-use bollard::container::{CPUStats, ListContainersOptions, MemoryStats, MemoryStatsStats};
+use bollard::container::{
+    CPUStats, InspectContainerOptions, ListContainersOptions, LogOutput, LogsOptions, MemoryStats,
+    MemoryStatsStats, RemoveContainerOptions, RenameContainerOptions, RestartContainerOptions,
+    Stats, StatsOptions, StopContainerOptions,
+};
+use bollard::errors::Error as BollardError;
+use bollard::models::{
+    ContainerInspectResponse, ContainerSummary, EventMessage, HostConfig, ImageInspect,
+    NetworkSettings, RestartPolicy, RestartPolicyNameEnum,
+};
+use bollard::system::EventsOptions;
+use chrono::{DateTime, Utc};
 use futures::StreamExt;
+use futures::stream::BoxStream;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::error::Error;
+use std::path::Path;
+use tokio::io::AsyncWriteExt;
 use tokio::time::{Duration, Instant};
 use tokio::{task::JoinHandle, time};
 
-use strip_ansi_escapes::strip;
+use strip_ansi_escapes::{strip, strip_str};
 
 use crate::app::SharedState;
+use crate::search;
 
 const MAX_LOG_LINES: usize = 1000;
+/// Shown in the IP column when a container has no address.
+pub const NO_IP: &str = "N/A";
 const CLEANUP_THRESHOLD: usize = 100;
+const MAX_COMMAND_WIDTH: usize = 30;
+const PREVIEW_LINES: usize = 5;
+const PREVIEW_DEBOUNCE: Duration = Duration::from_millis(300);
+const STATS_UNSUPPORTED: &str = "CPU/memory stats unsupported on this platform";
 
-fn calculate_cpu_usage(cpu_stats: CPUStats, pre_cpu_stats: CPUStats) -> Option<f64> {
+/// The daemon calls behind the container list, logs and stats, so tests can
+/// stand in canned responses for a real daemon.
+#[async_trait]
+pub trait DockerClient: Send + Sync {
+    async fn list_containers(
+        &self,
+        options: Option<ListContainersOptions<String>>,
+    ) -> Result<Vec<ContainerSummary>, BollardError>;
+
+    async fn inspect_container(
+        &self,
+        container_id: &str,
+        options: Option<InspectContainerOptions>,
+    ) -> Result<ContainerInspectResponse, BollardError>;
+
+    async fn inspect_image(&self, image_id: &str) -> Result<ImageInspect, BollardError>;
+
+    fn stats(
+        &self,
+        container_id: &str,
+        options: Option<StatsOptions>,
+    ) -> BoxStream<'static, Result<Stats, BollardError>>;
+
+    fn logs(
+        &self,
+        container_id: &str,
+        options: Option<LogsOptions<String>>,
+    ) -> BoxStream<'static, Result<LogOutput, BollardError>>;
+}
+
+#[async_trait]
+impl DockerClient for BollardDocker {
+    async fn list_containers(
+        &self,
+        options: Option<ListContainersOptions<String>>,
+    ) -> Result<Vec<ContainerSummary>, BollardError> {
+        BollardDocker::list_containers(self, options).await
+    }
+
+    async fn inspect_container(
+        &self,
+        container_id: &str,
+        options: Option<InspectContainerOptions>,
+    ) -> Result<ContainerInspectResponse, BollardError> {
+        BollardDocker::inspect_container(self, container_id, options).await
+    }
+
+    async fn inspect_image(&self, image_id: &str) -> Result<ImageInspect, BollardError> {
+        BollardDocker::inspect_image(self, image_id).await
+    }
+
+    fn stats(
+        &self,
+        container_id: &str,
+        options: Option<StatsOptions>,
+    ) -> BoxStream<'static, Result<Stats, BollardError>> {
+        BollardDocker::stats(self, container_id, options).boxed()
+    }
+
+    fn logs(
+        &self,
+        container_id: &str,
+        options: Option<LogsOptions<String>>,
+    ) -> BoxStream<'static, Result<LogOutput, BollardError>> {
+        BollardDocker::logs(self, container_id, options).boxed()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryUsage {
+    pub used: u64,
+    pub limit: u64,
+}
+
+impl MemoryUsage {
+    pub fn ratio(&self) -> f64 {
+        if self.limit == 0 {
+            return 0.0;
+        }
+        (self.used as f64 / self.limit as f64).clamp(0.0, 1.0)
+    }
+}
+
+/// Limits configured on the container; `None` means unlimited.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ResourceLimits {
+    pub cpus: Option<f64>,
+    pub memory: Option<u64>,
+}
+
+impl ResourceLimits {
+    fn from_host_config(host_config: &HostConfig) -> Self {
+        let positive = |value: Option<i64>| value.filter(|v| *v > 0);
+        let cpus = positive(host_config.nano_cpus)
+            .map(|nano_cpus| nano_cpus as f64 / 1e9)
+            .or_else(|| {
+                let quota = positive(host_config.cpu_quota)?;
+                let period = positive(host_config.cpu_period)?;
+                Some(quota as f64 / period as f64)
+            });
+        Self {
+            cpus,
+            memory: positive(host_config.memory).map(|memory| memory as u64),
+        }
+    }
+}
+
+/// Label Docker Compose puts on every container of a project.
+pub const COMPOSE_PROJECT_LABEL: &str = "com.docker.compose.project";
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ContainerInfo {
+    pub id: String,
+    pub image: String,
+    pub state: String,
+    pub status: String,
+    pub names: String,
+    pub command: String,
+    pub ip: String,
+    pub labels: BTreeMap<String, String>,
+    pub memory: Option<MemoryUsage>,
+    pub cpu_stats: Option<CPUStats>,
+    /// Set when `inspect` failed, so details like the IP are missing.
+    pub inspect_failed: bool,
+    /// The container's image has been removed, so starting it again will fail.
+    pub image_missing: bool,
+    /// e.g. `always` or `on-failure (max 5 retries)`; `None` if inspect failed.
+    pub restart_policy: Option<String>,
+    /// When the container was created, in Unix seconds.
+    pub created: Option<i64>,
+}
+
+impl ContainerInfo {
+    pub fn short_id(&self) -> &str {
+        &self.id[..self.id.len().min(12)]
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.state == "running"
+    }
+
+    /// Exit code of an exited container, read from a status like `Exited (137) 2 minutes ago`.
+    pub fn exit_code(&self) -> Option<i64> {
+        if self.state != "exited" {
+            return None;
+        }
+        let code = self.status.strip_prefix("Exited (")?.split(')').next()?;
+        code.parse().ok()
+    }
+
+    pub fn compose_project(&self) -> Option<&str> {
+        self.labels.get(COMPOSE_PROJECT_LABEL).map(String::as_str)
+    }
+
+    /// Best search score of the container's name or image; `None` if neither matches.
+    pub fn filter_score(&self, query: &str, fuzzy: bool) -> Option<i64> {
+        [&self.names, &self.image]
+            .iter()
+            .filter_map(|field| search::score(query, field, fuzzy))
+            .max()
+    }
+
+    /// CPU usage between an earlier sample of this container and this one.
+    pub fn cpu_usage_since(&self, previous: &ContainerInfo) -> Option<f64> {
+        calculate_cpu_usage(self.cpu_stats.as_ref()?, previous.cpu_stats.as_ref()?)
+    }
+
+    pub fn row(&self) -> Vec<String> {
+        vec![
+            self.short_id().to_string(),
+            if self.image_missing {
+                format!("{} (missing)", self.image)
+            } else {
+                self.image.clone()
+            },
+            self.status.clone(),
+            self.names.clone(),
+            truncate(&self.command, MAX_COMMAND_WIDTH),
+            if self.inspect_failed {
+                format!("{}?", self.ip)
+            } else {
+                self.ip.clone()
+            },
+        ]
+    }
+}
+
+/// Cuts `text` to at most `max_chars` characters, ending in "…" when shortened.
+pub fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let truncated: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    format!("{}…", truncated)
+}
+
+fn calculate_cpu_usage(cpu_stats: &CPUStats, pre_cpu_stats: &CPUStats) -> Option<f64> {
     let cpu_delta: f64 =
         cpu_stats.cpu_usage.total_usage as f64 - pre_cpu_stats.cpu_usage.total_usage as f64;
     let system_cpu_delta =
@@ -25,15 +244,21 @@ fn calculate_cpu_usage(cpu_stats: CPUStats, pre_cpu_stats: CPUStats) -> Option<f
     Some(cpu_usage)
 }
 
-fn calculate_memory_usage(mem_stats: MemoryStats) -> Option<f64> {
-    let cache = mem_stats.stats.map(|s| {
-        if let MemoryStatsStats::V1(v1) = s {
-            v1.cache
-        } else {
-            0
-        }
+fn calculate_used_memory(mem_stats: &MemoryStats) -> Option<u64> {
+    // Windows hosts have no cgroup usage, only the container's private working set.
+    if mem_stats.usage.is_none() {
+        return mem_stats.privateworkingset;
+    }
+    // Match `docker stats`: page cache doesn't count as used memory.
+    let cache = mem_stats.stats.map(|s| match s {
+        MemoryStatsStats::V1(v1) => v1.cache,
+        MemoryStatsStats::V2(v2) => v2.inactive_file,
     });
-    let used_memory = mem_stats.usage? - cache?;
+    Some(mem_stats.usage?.saturating_sub(cache?))
+}
+
+fn calculate_memory_usage(mem_stats: MemoryStats) -> Option<f64> {
+    let used_memory = calculate_used_memory(&mem_stats)?;
     let available_memory = mem_stats.limit?;
     if available_memory == 0 {
         return None;
@@ -41,85 +266,476 @@ fn calculate_memory_usage(mem_stats: MemoryStats) -> Option<f64> {
     Some((used_memory as f64 / available_memory as f64) * 100.0)
 }
 
+/// CPU and memory percentages at one point in time.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct UsageSnapshot {
+    pub cpu: Option<f64>,
+    pub memory: Option<f64>,
+}
+
+/// Takes one stats reading. Unlike `sample_stats`, the daemon waits for a
+/// second sample so CPU usage can be worked out on its own.
+pub async fn snapshot_usage(docker: &impl DockerClient, container_id: &str) -> UsageSnapshot {
+    let options = Some(StatsOptions {
+        stream: false,
+        one_shot: false,
+    });
+    let Some(Ok(stats)) = docker.stats(container_id, options).next().await else {
+        return UsageSnapshot::default();
+    };
+    UsageSnapshot {
+        cpu: calculate_cpu_usage(&stats.cpu_stats, &stats.precpu_stats),
+        memory: calculate_memory_usage(stats.memory_stats),
+    }
+}
+
+/// Fills in the usage shown in the context menu of a running container.
+pub fn fetch_menu_usage(container_id: String, app_state: SharedState) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let docker = match BollardDocker::connect_with_socket_defaults() {
+            Ok(docker) => docker,
+            Err(e) => {
+                let mut app = app_state.write().await;
+                app.diagnostics
+                    .warn(format!("Menu usage: can't connect to Docker: {e}"));
+                return;
+            }
+        };
+        let usage = snapshot_usage(&docker, &container_id).await;
+        let mut app = app_state.write().await;
+        if app.menu_usage_for.as_deref() == Some(container_id.as_str()) {
+            app.menu_usage = Some(usage);
+        }
+    })
+}
+
+async fn sample_stats(docker: &impl DockerClient, container_id: &str) -> Option<Stats> {
+    let options = Some(StatsOptions {
+        stream: false,
+        one_shot: true,
+    });
+    docker.stats(container_id, options).next().await?.ok()
+}
+
+fn memory_usage(mem_stats: &MemoryStats) -> Option<MemoryUsage> {
+    Some(MemoryUsage {
+        used: calculate_used_memory(mem_stats)?,
+        limit: mem_stats.limit?,
+    })
+}
+
+/// Mean of the collected samples, emptying them for the next window.
+fn take_average(samples: &mut Vec<f64>) -> Option<f64> {
+    if samples.is_empty() {
+        return None;
+    }
+    let average = samples.iter().sum::<f64>() / samples.len() as f64;
+    samples.clear();
+    Some(average)
+}
+
+/// Mirrors the daemon's `[a-zA-Z0-9][a-zA-Z0-9_.-]+` container name rule.
+pub fn is_valid_container_name(name: &str) -> bool {
+    let mut chars = name.strip_prefix('/').unwrap_or(name).chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+    let rest: Vec<char> = chars.collect();
+    first.is_ascii_alphanumeric()
+        && !rest.is_empty()
+        && rest
+            .iter()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+}
+
+pub async fn rename_container(container_id: &str, new_name: &str) -> Result<(), Box<dyn Error>> {
+    let docker = BollardDocker::connect_with_socket_defaults()?;
+    docker
+        .rename_container(container_id, RenameContainerOptions { name: new_name })
+        .await?;
+    Ok(())
+}
+
+pub async fn remove_container(container_id: &str) -> Result<(), Box<dyn Error>> {
+    let docker = BollardDocker::connect_with_socket_defaults()?;
+    docker
+        .remove_container(container_id, None::<RemoveContainerOptions>)
+        .await?;
+    Ok(())
+}
+
+/// Container actions that give the process time to shut down before it is killed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContainerAction {
+    Stop,
+    Restart,
+}
+
+impl ContainerAction {
+    pub fn label(self) -> &'static str {
+        match self {
+            ContainerAction::Stop => "Stop",
+            ContainerAction::Restart => "Restart",
+        }
+    }
+
+    pub fn past_tense(self) -> &'static str {
+        match self {
+            ContainerAction::Stop => "Stopped",
+            ContainerAction::Restart => "Restarted",
+        }
+    }
+}
+
+/// Runs `action`, letting Docker wait `timeout_secs` after SIGTERM before it sends SIGKILL.
+pub async fn run_container_action(
+    action: ContainerAction,
+    container_id: &str,
+    timeout_secs: u32,
+) -> Result<(), Box<dyn Error>> {
+    let docker = BollardDocker::connect_with_socket_defaults()?;
+    match action {
+        ContainerAction::Stop => {
+            let options = StopContainerOptions {
+                t: timeout_secs.into(),
+            };
+            docker.stop_container(container_id, Some(options)).await?;
+        }
+        ContainerAction::Restart => {
+            let options = RestartContainerOptions {
+                t: timeout_secs as isize,
+            };
+            docker
+                .restart_container(container_id, Some(options))
+                .await?;
+        }
+    }
+    Ok(())
+}
+
 pub fn stream_stats(container_id: String, app_state: SharedState) -> JoinHandle<()> {
     tokio::spawn(async move {
-        let docker = BollardDocker::connect_with_socket_defaults().unwrap();
-        let stream = &mut docker.stats(&container_id, None);
-        let start_time = Instant::now();
-
-        while let Some(result) = stream.next().await {
-            match result {
-                Ok(stats) => {
-                    let cpu_stats = stats.cpu_stats;
-                    let pre_cpu_stats = stats.precpu_stats;
-                    let timestamp = start_time.elapsed().as_secs_f64();
-                    let cpu_usage_result = calculate_cpu_usage(cpu_stats, pre_cpu_stats);
-                    let mut app = app_state.write().await;
-                    if let Some(cpu) = cpu_usage_result {
-                        app.cpu_data.add((timestamp, cpu));
-                    }
+        let docker = match BollardDocker::connect_with_socket_defaults() {
+            Ok(docker) => docker,
+            Err(e) => {
+                let mut app = app_state.write().await;
+                app.diagnostics.error(format!(
+                    "Stats of {container_id}: can't connect to Docker: {e}"
+                ));
+                app.stats_message = Some(format!("Error: {e}"));
+                return;
+            }
+        };
+        collect_stats(&docker, &container_id, &app_state).await;
+    })
+}
 
-                    let mem = calculate_memory_usage(stats.memory_stats);
-                    if let Some(mem) = mem {
-                        app.mem_data.add((timestamp, mem));
-                    }
+/// Feeds a container's stats stream into the CPU and memory windows until it ends.
+pub async fn collect_stats(
+    docker: &impl DockerClient,
+    container_id: &str,
+    app_state: &SharedState,
+) {
+    match docker.inspect_container(container_id, None).await {
+        Ok(info) => {
+            let limits = info
+                .host_config
+                .as_ref()
+                .map(ResourceLimits::from_host_config);
+            app_state.write().await.resource_limits = limits;
+        }
+        Err(e) => {
+            let mut app = app_state.write().await;
+            app.diagnostics
+                .warn(format!("Resource limits of {container_id} unknown: {e}"));
+        }
+    }
+    app_state
+        .write()
+        .await
+        .diagnostics
+        .info(format!("Opened stats stream of {container_id}"));
+    // The daemon sends a sample per second; graph points average `interval` of them.
+    let interval = app_state.read().await.config.stats_interval_secs.max(1);
+    let stream = &mut docker.stats(container_id, None);
+    let start_time = Instant::now();
+    let mut received_samples = false;
+    let mut pending = 0;
+    let mut cpu_samples = Vec::new();
+    let mut mem_samples = Vec::new();
+
+    while let Some(result) = stream.next().await {
+        match result {
+            Ok(stats) => {
+                received_samples = true;
+                cpu_samples.extend(calculate_cpu_usage(&stats.cpu_stats, &stats.precpu_stats));
+                mem_samples.extend(calculate_memory_usage(stats.memory_stats));
+                pending += 1;
+                if pending < interval {
+                    continue;
+                }
+                pending = 0;
+
+                let timestamp = start_time.elapsed().as_secs_f64();
+                let mut app = app_state.write().await;
+                if let Some(cpus) = stats.cpu_stats.online_cpus {
+                    app.online_cpus = Some(cpus);
+                }
+                if let Some(cpu) = take_average(&mut cpu_samples) {
+                    app.cpu_data.add((timestamp, cpu));
                 }
-                Err(e) => eprintln!("Error: {}", e),
+                if let Some(mem) = take_average(&mut mem_samples) {
+                    app.mem_data.add((timestamp, mem));
+                }
+
+                // Windows stats lack the fields both calculations rely on.
+                if app.cpu_data.data.is_empty() && app.mem_data.data.is_empty() {
+                    app.stats_message = Some(STATS_UNSUPPORTED.to_string());
+                } else if app.stats_message.as_deref() == Some(STATS_UNSUPPORTED) {
+                    app.stats_message = None;
+                }
+            }
+            Err(e) => {
+                let mut app = app_state.write().await;
+                app.diagnostics
+                    .warn(format!("Stats stream of {container_id}: {e}"));
             }
         }
-    })
+    }
+
+    if !received_samples {
+        let mut app = app_state.write().await;
+        app.stats_message = Some("No stats available (container not running)".to_string());
+    }
 }
 
-pub fn stream_logs(container_id: String, app_state: SharedState) -> JoinHandle<()> {
+/// Streams a container's logs into `app.logs`. Exited containers can't be
+/// followed, so their history is fetched once instead. With `since`, only
+/// lines from that Unix time on are appended, e.g. after a reconnect.
+pub fn stream_logs(
+    container_id: String,
+    follow: bool,
+    since: Option<DateTime<Utc>>,
+    app_state: SharedState,
+) -> JoinHandle<()> {
     tokio::spawn(async move {
-        let docker = BollardDocker::connect_with_socket_defaults().unwrap();
+        let docker = match BollardDocker::connect_with_socket_defaults() {
+            Ok(docker) => docker,
+            Err(e) => {
+                let mut app = app_state.write().await;
+                app.diagnostics.error(format!(
+                    "Logs of {container_id}: can't connect to Docker: {e}"
+                ));
+                app.logs_loading = false;
+                app.logs.clear();
+                app.log_error = Some(format!("Error connecting to Docker: {e}"));
+                return;
+            }
+        };
 
-        let options = Some(bollard::container::LogsOptions {
-            follow: true,
-            stdout: true,
-            stderr: true,
-            tail: "2000",
-            ..Default::default()
-        });
+        collect_logs(&docker, &container_id, follow, since, &app_state).await;
+    })
+}
+
+/// Appends a container's log stream to `app.logs` in batches until it ends.
+/// When resuming `since` the newest line shown, lines up to it are skipped.
+pub async fn collect_logs(
+    docker: &impl DockerClient,
+    container_id: &str,
+    follow: bool,
+    since: Option<DateTime<Utc>>,
+    app_state: &SharedState,
+) {
+    // Docker only filters by whole seconds; the timestamps it prefixes lines
+    // with tell apart what was already shown within that second.
+    let options = Some(bollard::container::LogsOptions {
+        follow,
+        stdout: true,
+        stderr: true,
+        since: since.map(|since| since.timestamp()).unwrap_or_default(),
+        timestamps: true,
+        tail: if since.is_some() { "all" } else { "2000" }.to_string(),
+        ..Default::default()
+    });
+    let mut last_time = None;
+
+    app_state
+        .write()
+        .await
+        .diagnostics
+        .info(format!("Opened log stream of {container_id}"));
+    let mut log_stream = docker.logs(container_id, options);
+    let mut new_lines_since_cleanup = 0;
+    let mut buffer: Vec<String> = Vec::new();
+
+    let flush_interval = Duration::from_millis(100);
+    let mut interval = time::interval(flush_interval);
 
-        let mut log_stream = docker.logs(&container_id, options);
-        let mut new_lines_since_cleanup = 0;
-        let mut buffer: Vec<String> = Vec::new();
-
-        let flush_interval = Duration::from_millis(100);
-        let mut interval = time::interval(flush_interval);
-
-        loop {
-            tokio::select! {
-                maybe_line = log_stream.next() => {
-                    match maybe_line {
-                        Some(Ok(chunk)) => {
-                            let cleaned = strip(chunk);
-                            let line = String::from_utf8_lossy(&cleaned).to_string();
-                            buffer.push(line);
-                            new_lines_since_cleanup += 1;
+    loop {
+        tokio::select! {
+            maybe_line = log_stream.next() => {
+                match maybe_line {
+                    Some(Ok(chunk)) => {
+                        // Escape sequences are kept so logs can be rendered in color.
+                        let (line, time) = split_log_timestamps(&String::from_utf8_lossy(&chunk.into_bytes()));
+                        if since.is_some_and(|since| time.is_some_and(|time| time <= since)) {
+                            continue;
                         }
-                        Some(Err(e)) => {
-                            let mut app = app_state.write().await;
-                            app.logs.push(format!("Error streaming logs: {e}"));
+                        last_time = time.or(last_time);
+                        buffer.push(line);
+                        new_lines_since_cleanup += 1;
+                    }
+                    Some(Err(e)) => {
+                        flush_buffer(container_id, &mut buffer, last_time, app_state, &mut new_lines_since_cleanup).await;
+                        let mut app = app_state.write().await;
+                        app.diagnostics.warn(format!("Log stream of {container_id}: {e}"));
+                        if app.shows_logs_of(container_id) {
+                            app.log_error = Some(format!("Error streaming logs: {}", describe_error(&e)));
+                            app.last_log_time.get_or_insert_with(Utc::now);
                         }
-                        None => {
-                            flush_buffer(&mut buffer, &app_state, &mut new_lines_since_cleanup).await;
-                            break;
+                    }
+                    None => {
+                        flush_buffer(container_id, &mut buffer, last_time, app_state, &mut new_lines_since_cleanup).await;
+                        let mut app = app_state.write().await;
+                        if app.shows_logs_of(container_id) {
+                            app.last_log_time.get_or_insert_with(Utc::now);
+                            if app.logs_loading {
+                                app.logs_loading = false;
+                                app.logs = vec!["No logs".to_string()];
+                            }
                         }
+                        break;
                     }
                 }
-                _ = interval.tick() => {
-                            flush_buffer(&mut buffer, &app_state, &mut new_lines_since_cleanup).await;
+            }
+            _ = interval.tick() => {
+                        flush_buffer(container_id, &mut buffer, last_time, app_state, &mut new_lines_since_cleanup).await;
 
-                    }
+                }
+        }
+    }
+}
+
+/// Removes the timestamp Docker prefixes each line of `chunk` with, returning
+/// the text and the newest timestamp found. Chunks without one pass unchanged.
+fn split_log_timestamps(chunk: &str) -> (String, Option<DateTime<Utc>>) {
+    let mut newest = None;
+    let text = chunk
+        .split_inclusive('\n')
+        .map(|line| {
+            let parsed = line.split_once(' ').and_then(|(prefix, rest)| {
+                Some((DateTime::parse_from_rfc3339(prefix).ok()?, rest))
+            });
+            match parsed {
+                Some((time, rest)) => {
+                    newest = Some(time.with_timezone(&Utc));
+                    rest
+                }
+                None => line,
+            }
+        })
+        .collect();
+    (text, newest)
+}
+
+/// Writes the container's complete log history to `path`, bypassing the in-memory
+/// buffer and its `MAX_LOG_LINES` trimming.
+pub async fn export_logs(
+    docker: &impl DockerClient,
+    container_id: &str,
+    path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let options = Some(bollard::container::LogsOptions::<String> {
+        follow: false,
+        stdout: true,
+        stderr: true,
+        tail: "all".to_string(),
+        ..Default::default()
+    });
+
+    let mut log_stream = docker.logs(container_id, options);
+    let mut file = tokio::fs::File::create(path).await?;
+    while let Some(chunk) = log_stream.next().await {
+        file.write_all(&strip(chunk?)).await?;
+    }
+    file.flush().await?;
+    Ok(())
+}
+
+/// Writes the last `tail` log lines of a container to `out`, for printing
+/// without the UI. Escape sequences are stripped unless `raw`.
+pub async fn write_logs(
+    docker: &impl DockerClient,
+    container: &str,
+    tail: usize,
+    raw: bool,
+    out: &mut impl std::io::Write,
+) -> Result<(), Box<dyn Error>> {
+    let options = Some(bollard::container::LogsOptions::<String> {
+        follow: false,
+        stdout: true,
+        stderr: true,
+        tail: tail.to_string(),
+        ..Default::default()
+    });
+
+    let mut log_stream = docker.logs(container, options);
+    while let Some(chunk) = log_stream.next().await {
+        let bytes = chunk.map_err(|e| describe_error(&e))?.into_bytes();
+        if raw {
+            out.write_all(&bytes)?;
+        } else {
+            out.write_all(&strip(bytes))?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Fetches the last few log lines of a container for the Normal-mode preview.
+/// Waits briefly first so scrolling through the list doesn't fetch every row;
+/// the caller aborts the task when the selection moves on.
+pub fn fetch_preview(container_id: String, app_state: SharedState) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        time::sleep(PREVIEW_DEBOUNCE).await;
+        let docker = match BollardDocker::connect_with_socket_defaults() {
+            Ok(docker) => docker,
+            Err(e) => {
+                let mut app = app_state.write().await;
+                app.diagnostics
+                    .warn(format!("Log preview: can't connect to Docker: {e}"));
+                return;
             }
+        };
+        let options = Some(bollard::container::LogsOptions {
+            follow: false,
+            stdout: true,
+            stderr: true,
+            tail: PREVIEW_LINES.to_string(),
+            ..Default::default()
+        });
+        let lines: Vec<String> = docker
+            .logs(&container_id, options)
+            .filter_map(|chunk| async move { chunk.ok() })
+            .map(|chunk| {
+                String::from_utf8_lossy(&strip(chunk.into_bytes()))
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+            .await;
+
+        let mut app = app_state.write().await;
+        if app.preview_for.as_deref() == Some(container_id.as_str()) {
+            app.preview_logs = lines;
         }
     })
 }
 
 async fn flush_buffer(
+    container_id: &str,
     buffer: &mut Vec<String>,
+    last_time: Option<DateTime<Utc>>,
     app_state: &SharedState,
     new_lines_since_cleanup: &mut usize,
 ) {
@@ -128,7 +744,46 @@ async fn flush_buffer(
     }
 
     let mut app = app_state.write().await;
+    // Parked streams keep their own buffer whatever is shown meanwhile.
+    if let Some(position) = app
+        .parked_logs
+        .iter()
+        .position(|(id, _)| id == container_id)
+    {
+        let app = &mut *app;
+        if app.log_filter_enabled {
+            buffer.retain(|line| app.config.log_filter.keeps(&strip_str(line)));
+        }
+        let parked = &mut app.parked_logs[position].1;
+        parked.last_log_time = last_time.or(parked.last_log_time);
+        parked.logs.append(buffer);
+        let excess = parked.logs.len().saturating_sub(MAX_LOG_LINES);
+        parked.logs.drain(..excess);
+        parked.truncated |= excess > 0;
+        return;
+    }
+    if !app.shows_logs_of(container_id) {
+        buffer.clear();
+        return;
+    }
+    let now = std::time::Instant::now();
+    let bytes = buffer.iter().map(String::len).sum();
+    if app.logs_loading {
+        app.logs_loading = false;
+        app.logs.clear();
+        app.log_throughput.record_backlog(buffer.len(), bytes, now);
+    } else {
+        app.log_throughput.record(buffer.len(), bytes, now);
+    }
+    if app.log_filter_enabled {
+        let received = buffer.len();
+        buffer.retain(|line| app.config.log_filter.keeps(&strip_str(line)));
+        app.hidden_log_lines += received - buffer.len();
+    }
+    app.last_log_flush = Some((buffer.len(), now));
+    app.last_log_time = last_time.or(app.last_log_time);
     app.logs.append(buffer);
+    app.locate_unread();
     let number_of_log_lines = app.logs.len();
 
     if !app.user_scrolled {
@@ -143,48 +798,537 @@ async fn flush_buffer(
         if number_of_log_lines > MAX_LOG_LINES {
             let excess = number_of_log_lines - MAX_LOG_LINES;
             app.logs.drain(0..excess);
+            app.logs_truncated = true;
+            app.unread_from = app.unread_from.map(|from| from.saturating_sub(excess));
         }
         *new_lines_since_cleanup = 0;
     }
 }
 
-pub async fn get_container_data() -> Result<Vec<(String, Vec<String>)>, Box<dyn Error>> {
-    let docker = BollardDocker::connect_with_socket_defaults().unwrap();
+/// Container actions that change what the table shows.
+const LIFECYCLE_EVENTS: [&str; 9] = [
+    "create",
+    "start",
+    "stop",
+    "die",
+    "destroy",
+    "rename",
+    "pause",
+    "unpause",
+    "health_status",
+];
+
+/// Refreshes the container list whenever the daemon reports a lifecycle event,
+/// so the table updates without waiting for the next keypress.
+pub fn watch_events(app_state: SharedState) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let docker = match BollardDocker::connect_with_socket_defaults() {
+            Ok(docker) => docker,
+            Err(e) => {
+                let mut app = app_state.write().await;
+                app.diagnostics.error(format!(
+                    "Can't watch Docker events, the list won't update on its own: {e}"
+                ));
+                return;
+            }
+        };
+        let filters = HashMap::from([
+            ("type".to_string(), vec!["container".to_string()]),
+            (
+                "event".to_string(),
+                LIFECYCLE_EVENTS.iter().map(|e| e.to_string()).collect(),
+            ),
+        ]);
+        let mut events = docker.events(Some(EventsOptions::<String> {
+            filters,
+            ..Default::default()
+        }));
+
+        while let Some(Ok(event)) = events.next().await {
+            let (paused, label_filter, sample_usage) = {
+                let mut app = app_state.write().await;
+                if let Some(description) = describe_event(&event) {
+                    app.diagnostics.debug(format!("Event: {description}"));
+                    app.push_event(event.time.unwrap_or_default(), description);
+                }
+                if event.action.as_deref() == Some("destroy")
+                    && let Some(id) = event.actor.as_ref().and_then(|actor| actor.id.as_deref())
+                {
+                    app.forget_removed_container(id);
+                }
+                (app.paused, app.label_filter.clone(), app.samples_usage())
+            };
+            if paused {
+                continue;
+            }
+            let result = get_container_data(label_filter.as_deref(), sample_usage)
+                .await
+                .map_err(|e| e.to_string());
+            app_state.write().await.apply_refresh(result);
+        }
+        app_state
+            .write()
+            .await
+            .diagnostics
+            .warn("Docker event stream ended, the list won't update on its own");
+    })
+}
+
+/// One-line summary such as `web died (137)`, shown after the event's time.
+fn describe_event(event: &EventMessage) -> Option<String> {
+    let action = event.action.as_deref()?;
+    let attributes = event
+        .actor
+        .as_ref()
+        .and_then(|actor| actor.attributes.as_ref());
+    let attribute = |key: &str| attributes.and_then(|attributes| attributes.get(key));
+    let name = attribute("name")
+        .cloned()
+        .or_else(|| {
+            let id = event.actor.as_ref()?.id.as_deref()?;
+            Some(id[..id.len().min(12)].to_string())
+        })
+        .unwrap_or_default();
+    let what = match action {
+        "create" => "created".to_string(),
+        "start" => "started".to_string(),
+        "stop" => "stopped".to_string(),
+        "die" => match attribute("exitCode") {
+            Some(code) => format!("died ({code})"),
+            None => "died".to_string(),
+        },
+        "destroy" => "removed".to_string(),
+        "rename" => "renamed".to_string(),
+        "pause" => "paused".to_string(),
+        "unpause" => "unpaused".to_string(),
+        other => match other.strip_prefix("health_status: ") {
+            Some(health) => format!("is {health}"),
+            None => other.to_string(),
+        },
+    };
+    Some(format!("{name} {what}"))
+}
+
+pub async fn get_container_data(
+    label_filter: Option<&str>,
+    sample_usage: bool,
+) -> Result<Vec<ContainerInfo>, Box<dyn Error>> {
+    let docker = BollardDocker::connect_with_socket_defaults()?;
+    fetch_container_data(&docker, label_filter, sample_usage).await
+}
+
+/// Builds the table rows from the container list, inspecting each container
+/// and image and, with `sample_usage`, taking one stats sample per running container.
+pub async fn fetch_container_data(
+    docker: &impl DockerClient,
+    label_filter: Option<&str>,
+    sample_usage: bool,
+) -> Result<Vec<ContainerInfo>, Box<dyn Error>> {
+    let mut filters = HashMap::new();
+    if let Some(label) = label_filter {
+        filters.insert("label".to_string(), vec![label.to_string()]);
+    }
     let containers = &docker
         .list_containers(Some(ListContainersOptions::<String> {
             all: true,
+            filters,
             ..Default::default()
         }))
         .await
-        .unwrap();
+        .map_err(|e| describe_error(&e))?;
 
-    let container_data: Vec<(String, Vec<String>)> =
-        futures::future::join_all(containers.clone().into_iter().map(|container| async {
+    let image_ids: HashSet<&str> = containers
+        .iter()
+        .filter_map(|container| container.image_id.as_deref())
+        .collect();
+    let missing_images: HashSet<&str> =
+        futures::future::join_all(image_ids.into_iter().map(|image_id| async move {
+            let result = docker.inspect_image(image_id).await;
+            is_not_found(result.err()?).then_some(image_id)
+        }))
+        .await
+        .into_iter()
+        .flatten()
+        .collect();
+
+    let missing_images = &missing_images;
+    let mut container_data: Vec<ContainerInfo> =
+        futures::future::join_all(containers.clone().into_iter().map(|container| async move {
             let id = container.id.unwrap_or_default();
+            let image_missing = container
+                .image_id
+                .is_some_and(|image_id| missing_images.contains(image_id.as_str()));
 
-            let ip = docker
-                .inspect_container(&id, None)
-                .await
-                .ok()
-                .map(|info| info.network_settings)
-                .and_then(|network_settings| {
-                    if let Some(network_settings) = network_settings {
-                        network_settings.ip_address
-                    } else {
-                        None
-                    }
-                })
-                .unwrap_or("N/A".to_string());
-
-            let row = vec![
-                id[..12].to_string(),
-                container.image.unwrap_or_default(),
-                container.status.unwrap_or_default(),
-                container.names.unwrap_or_default().join(", "),
+            // Containers can vanish mid-refresh; a failed inspect only affects this row.
+            let inspect = docker.inspect_container(&id, None).await.ok();
+            let inspect_failed = inspect.is_none();
+            let restart_policy = inspect
+                .as_ref()
+                .and_then(|info| info.host_config.as_ref()?.restart_policy.as_ref())
+                .map(describe_restart_policy);
+            let ip = inspect
+                .and_then(|info| container_ip(&info.network_settings?))
+                .unwrap_or(NO_IP.to_string());
+
+            let mut info = ContainerInfo {
+                image: container.image.unwrap_or_default(),
+                state: container.state.unwrap_or_default(),
+                status: container.status.unwrap_or_default(),
+                names: container.names.unwrap_or_default().join(", "),
+                command: container.command.unwrap_or_default(),
+                labels: container.labels.unwrap_or_default().into_iter().collect(),
+                id,
                 ip,
-            ];
-            (id, row)
+                memory: None,
+                cpu_stats: None,
+                inspect_failed,
+                image_missing,
+                restart_policy,
+                created: container.created,
+            };
+            if sample_usage
+                && info.is_running()
+                && let Some(stats) = sample_stats(docker, &info.id).await
+            {
+                info.memory = memory_usage(&stats.memory_stats);
+                info.cpu_stats = Some(stats.cpu_stats);
+            }
+            info
         }))
         .await;
+    // Docker's listing order shifts as containers come and go; a stable sort keeps rows put.
+    container_data.sort_by(|a, b| a.names.cmp(&b.names));
     Ok(container_data)
 }
+
+/// The default bridge IP, or else the IPs on user-defined networks, which
+/// leave the top-level address empty.
+fn container_ip(network_settings: &NetworkSettings) -> Option<String> {
+    if let Some(ip) = network_settings
+        .ip_address
+        .as_ref()
+        .filter(|ip| !ip.is_empty())
+    {
+        return Some(ip.clone());
+    }
+    let networks: BTreeMap<_, _> = network_settings.networks.iter().flatten().collect();
+    let ips: Vec<&str> = networks
+        .values()
+        .filter_map(|endpoint| endpoint.ip_address.as_deref())
+        .filter(|ip| !ip.is_empty())
+        .collect();
+    (!ips.is_empty()).then(|| ips.join(", "))
+}
+
+fn describe_restart_policy(policy: &RestartPolicy) -> String {
+    match (policy.name, policy.maximum_retry_count) {
+        (None | Some(RestartPolicyNameEnum::EMPTY), _) => "no".to_string(),
+        (Some(RestartPolicyNameEnum::ON_FAILURE), Some(retries)) if retries > 0 => {
+            format!("on-failure (max {retries} retries)")
+        }
+        (Some(name), _) => name.to_string(),
+    }
+}
+
+/// Shown instead of the connection error a socket without access produces.
+pub const PERMISSION_DENIED: &str =
+    "Permission denied accessing Docker socket — are you in the docker group?";
+
+/// The error's message, or what to do about it when it's a permission problem
+/// with the socket, which otherwise reads as a bare connection failure.
+pub fn describe_error(error: &(dyn Error + 'static)) -> String {
+    let mut source = Some(error);
+    while let Some(error) = source {
+        // Bollard's own IO errors are transparent, so their source skips the IO error.
+        let io = match error.downcast_ref::<BollardError>() {
+            Some(BollardError::IOError { err }) => Some(err),
+            _ => error.downcast_ref::<std::io::Error>(),
+        };
+        if io.is_some_and(|io| io.kind() == std::io::ErrorKind::PermissionDenied) {
+            return PERMISSION_DENIED.to_string();
+        }
+        source = error.source();
+    }
+    error.to_string()
+}
+
+fn is_not_found(error: bollard::errors::Error) -> bool {
+    matches!(
+        error,
+        bollard::errors::Error::DockerResponseServerError {
+            status_code: 404,
+            ..
+        }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use bollard::models::{EndpointSettings, EventActor};
+
+    use super::*;
+
+    #[test]
+    fn row_truncates_long_commands() {
+        let info = ContainerInfo {
+            id: "0123456789abcdef".into(),
+            command: "x".repeat(MAX_COMMAND_WIDTH + 5),
+            ..Default::default()
+        };
+        let row = info.row();
+        assert_eq!("0123456789ab", row[0]);
+        assert_eq!(MAX_COMMAND_WIDTH, row[4].chars().count());
+        assert!(row[4].ends_with('…'));
+    }
+
+    #[test]
+    fn explains_permission_errors() {
+        let denied = BollardError::IOError {
+            err: std::io::Error::from(std::io::ErrorKind::PermissionDenied),
+        };
+        assert_eq!(PERMISSION_DENIED, describe_error(&denied));
+        let refused = BollardError::IOError {
+            err: std::io::Error::from(std::io::ErrorKind::ConnectionRefused),
+        };
+        assert_eq!(refused.to_string(), describe_error(&refused));
+    }
+
+    #[test]
+    fn reads_exit_codes_from_the_status() {
+        let container = |state: &str, status: &str| ContainerInfo {
+            state: state.into(),
+            status: status.into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            Some(137),
+            container("exited", "Exited (137) 2 minutes ago").exit_code()
+        );
+        assert_eq!(
+            Some(0),
+            container("exited", "Exited (0) 1 second ago").exit_code()
+        );
+        assert_eq!(None, container("running", "Up 3 hours").exit_code());
+        assert_eq!(None, container("exited", "Created").exit_code());
+    }
+
+    #[test]
+    fn filter_matches_subsequences_of_name_or_image() {
+        let info = ContainerInfo {
+            names: "web-frontend".into(),
+            image: "nginx:latest".into(),
+            ..Default::default()
+        };
+        assert!(info.filter_score("wb", true).is_some());
+        assert!(info.filter_score("ngx", true).is_some());
+        assert!(info.filter_score("bw", true).is_none());
+        assert!(info.filter_score("wb", false).is_none());
+    }
+
+    #[test]
+    fn reads_ips_from_user_defined_networks() {
+        let endpoint = |ip: &str| EndpointSettings {
+            ip_address: Some(ip.to_string()),
+            ..Default::default()
+        };
+        let mut settings = NetworkSettings {
+            ip_address: Some(String::new()),
+            networks: Some(HashMap::from([
+                ("frontend".to_string(), endpoint("172.19.0.2")),
+                ("backend".to_string(), endpoint("172.20.0.2")),
+                ("none".to_string(), endpoint("")),
+            ])),
+            ..Default::default()
+        };
+        assert_eq!(
+            Some("172.20.0.2, 172.19.0.2".to_string()),
+            container_ip(&settings)
+        );
+
+        settings.ip_address = Some("172.17.0.2".to_string());
+        assert_eq!(Some("172.17.0.2".to_string()), container_ip(&settings));
+        assert_eq!(None, container_ip(&NetworkSettings::default()));
+    }
+
+    #[test]
+    fn describes_restart_policies() {
+        let policy = |name, maximum_retry_count| RestartPolicy {
+            name,
+            maximum_retry_count,
+        };
+        assert_eq!("no", describe_restart_policy(&policy(None, None)));
+        assert_eq!(
+            "no",
+            describe_restart_policy(&policy(Some(RestartPolicyNameEnum::EMPTY), None))
+        );
+        assert_eq!(
+            "always",
+            describe_restart_policy(&policy(Some(RestartPolicyNameEnum::ALWAYS), Some(0)))
+        );
+        assert_eq!(
+            "on-failure (max 5 retries)",
+            describe_restart_policy(&policy(Some(RestartPolicyNameEnum::ON_FAILURE), Some(5)))
+        );
+        assert_eq!(
+            "on-failure",
+            describe_restart_policy(&policy(Some(RestartPolicyNameEnum::ON_FAILURE), Some(0)))
+        );
+    }
+
+    #[test]
+    fn row_marks_missing_image() {
+        let info = ContainerInfo {
+            image: "web:old".into(),
+            image_missing: true,
+            ..Default::default()
+        };
+        assert_eq!("web:old (missing)", info.row()[1]);
+    }
+
+    #[test]
+    fn row_marks_failed_inspect() {
+        let info = ContainerInfo {
+            ip: "N/A".into(),
+            inspect_failed: true,
+            ..Default::default()
+        };
+        assert_eq!("N/A?", info.row()[5]);
+    }
+
+    #[test]
+    fn validates_container_names() {
+        assert!(is_valid_container_name("web-1"));
+        assert!(is_valid_container_name("/my_app.v2"));
+        assert!(!is_valid_container_name(""));
+        assert!(!is_valid_container_name("a"));
+        assert!(!is_valid_container_name("-web"));
+        assert!(!is_valid_container_name("web app"));
+        assert!(!is_valid_container_name("web/app"));
+    }
+
+    fn memory_stats(usage: Option<u64>, limit: Option<u64>) -> MemoryStats {
+        MemoryStats {
+            stats: None,
+            max_usage: None,
+            usage,
+            failcnt: None,
+            limit,
+            commit: None,
+            commit_peak: None,
+            commitbytes: None,
+            commitpeakbytes: None,
+            privateworkingset: None,
+        }
+    }
+
+    #[test]
+    fn windows_memory_uses_private_working_set() {
+        let stats = MemoryStats {
+            privateworkingset: Some(4096),
+            commitbytes: Some(8192),
+            ..memory_stats(None, None)
+        };
+        assert_eq!(Some(4096), calculate_used_memory(&stats));
+        // Windows reports no limit, so there is no percentage to plot.
+        assert_eq!(None, calculate_memory_usage(stats));
+    }
+
+    #[test]
+    fn cgroup_v2_memory_excludes_inactive_file() {
+        let fixture = r#"{
+            "usage": 104857600,
+            "limit": 1073741824,
+            "stats": {
+                "anon": 41943040, "file": 62914560, "kernel_stack": 0, "slab": 0,
+                "sock": 0, "shmem": 0, "file_mapped": 0, "file_dirty": 0,
+                "file_writeback": 0, "anon_thp": 0, "inactive_anon": 0,
+                "active_anon": 41943040, "inactive_file": 52428800,
+                "active_file": 10485760, "unevictable": 0, "slab_reclaimable": 0,
+                "slab_unreclaimable": 0, "pgfault": 0, "pgmajfault": 0,
+                "workingset_refault": 0, "workingset_activate": 0,
+                "workingset_nodereclaim": 0, "pgrefill": 0, "pgscan": 0,
+                "pgsteal": 0, "pgactivate": 0, "pgdeactivate": 0, "pglazyfree": 0,
+                "pglazyfreed": 0, "thp_fault_alloc": 0, "thp_collapse_alloc": 0
+            }
+        }"#;
+        let stats: MemoryStats = serde_json::from_str(fixture).unwrap();
+        assert!(matches!(stats.stats, Some(MemoryStatsStats::V2(_))));
+        assert_eq!(Some(52428800), calculate_used_memory(&stats));
+        assert_eq!(Some(100.0 * 50.0 / 1024.0), calculate_memory_usage(stats));
+    }
+
+    fn event(action: &str, attributes: &[(&str, &str)]) -> EventMessage {
+        EventMessage {
+            action: Some(action.to_string()),
+            actor: Some(EventActor {
+                id: Some("0123456789abcdef".to_string()),
+                attributes: Some(
+                    attributes
+                        .iter()
+                        .map(|(k, v)| (k.to_string(), v.to_string()))
+                        .collect(),
+                ),
+            }),
+            time: Some(3600 * 12 + 60 * 3 + 4),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn splits_docker_timestamps_off_log_lines() {
+        let (text, time) =
+            split_log_timestamps("2023-11-14T22:13:20.1Z first\n2023-11-14T22:13:21.5Z second\n");
+        assert_eq!("first\nsecond\n", text);
+        assert_eq!(DateTime::from_timestamp(1_700_000_001, 500_000_000), time);
+        assert_eq!(
+            ("no timestamp here\n".to_string(), None),
+            split_log_timestamps("no timestamp here\n")
+        );
+    }
+
+    #[test]
+    fn describes_events() {
+        assert_eq!(
+            Some("web started".to_string()),
+            describe_event(&event("start", &[("name", "web")]))
+        );
+        assert_eq!(
+            Some("db died (137)".to_string()),
+            describe_event(&event("die", &[("name", "db"), ("exitCode", "137")]))
+        );
+        assert_eq!(
+            Some("0123456789ab is unhealthy".to_string()),
+            describe_event(&event("health_status: unhealthy", &[]))
+        );
+    }
+
+    #[test]
+    fn reads_resource_limits_from_host_config() {
+        let limits = ResourceLimits::from_host_config(&HostConfig {
+            nano_cpus: Some(2_000_000_000),
+            memory: Some(512 * 1024 * 1024),
+            ..Default::default()
+        });
+        assert_eq!(Some(2.0), limits.cpus);
+        assert_eq!(Some(512 * 1024 * 1024), limits.memory);
+
+        let limits = ResourceLimits::from_host_config(&HostConfig {
+            cpu_quota: Some(50_000),
+            cpu_period: Some(100_000),
+            memory: Some(0),
+            ..Default::default()
+        });
+        assert_eq!(Some(0.5), limits.cpus);
+        assert_eq!(None, limits.memory);
+
+        assert_eq!(
+            ResourceLimits::default(),
+            ResourceLimits::from_host_config(&HostConfig::default())
+        );
+    }
+
+    #[test]
+    fn truncate_keeps_short_text() {
+        assert_eq!("sleep 10", truncate("sleep 10", MAX_COMMAND_WIDTH));
+    }
+}
@@ -1,179 +1,591 @@
-use ratatui::crossterm::event::KeyCode;
+use std::collections::BTreeSet;
 
-use crate::app::{AppMode, AppState};
+use chrono::Utc;
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use strip_ansi_escapes::strip_str;
+
+use crate::{
+    app::{AppMode, AppState, LOADING_LOGS},
+    config::{EnterAction, LogAnchor},
+    docker::{ContainerAction, is_valid_container_name},
+    search,
+};
+
+const OVERLAY_RESIZE_STEP: i16 = 5;
 
 pub struct KeyBinding {
-    pub keys: Vec<KeyCode>,
+    pub keys: Vec<KeyEvent>,
     pub description: &'static str,
-    pub action: fn(&mut AppState, &KeyCode),
+    pub action: fn(&mut AppState, &KeyEvent),
+}
+
+impl KeyBinding {
+    /// Shift is part of the key code for characters (`G` vs `g`), so it is ignored here.
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        let modifiers = event.modifiers - KeyModifiers::SHIFT;
+        self.keys
+            .iter()
+            .any(|key| key.code == event.code && key.modifiers - KeyModifiers::SHIFT == modifiers)
+    }
+}
+
+/// A context menu entry; `key` activates it directly while the menu is open.
+pub struct MenuItem {
+    pub key: char,
+    pub label: &'static str,
+    pub action: fn(&mut AppState),
+}
+
+pub fn menu_items() -> Vec<MenuItem> {
+    vec![
+        MenuItem {
+            key: 'l',
+            label: "Logs",
+            action: |app| {
+                app.close_stats();
+                open_logs(app);
+                app.split_view = false;
+            },
+        },
+        MenuItem {
+            key: 's',
+            label: "Stats",
+            action: |app| {
+                app.stop_streams();
+                app.mode = AppMode::Resources;
+            },
+        },
+        MenuItem {
+            key: 'd',
+            label: "Details",
+            action: |app| app.mode = AppMode::Details,
+        },
+        MenuItem {
+            key: 'b',
+            label: "Labels",
+            action: |app| {
+                app.mode = AppMode::Labels;
+                app.vertical_scroll = 0;
+            },
+        },
+        MenuItem {
+            key: 'n',
+            label: "Rename",
+            action: |app| {
+                if let Some(container) = app.selected_container() {
+                    let name = container.names.trim_start_matches('/').to_string();
+                    app.rename_input.set(name);
+                    app.mode = AppMode::Rename;
+                }
+            },
+        },
+        MenuItem {
+            key: 'r',
+            label: "Restart",
+            action: |app| {
+                let targets = app.selected_container().map(|c| c.id.clone());
+                let confirm = app.config.confirm.restart;
+                start_action(
+                    app,
+                    ContainerAction::Restart,
+                    targets.into_iter().collect(),
+                    confirm,
+                );
+            },
+        },
+        MenuItem {
+            key: 'x',
+            label: "Remove",
+            action: |app| {
+                if app.config.confirm.remove {
+                    app.confirm_input.clear();
+                    app.mode = AppMode::ConfirmRemove;
+                } else {
+                    app.pending_remove = app.selected_container().map(|c| c.id.clone());
+                    app.mode = AppMode::Normal;
+                }
+            },
+        },
+        MenuItem {
+            key: 'o',
+            label: "Logs + Stats",
+            action: |app| {
+                open_logs(app);
+                app.split_view = true;
+            },
+        },
+        MenuItem {
+            key: 't',
+            label: "Stop",
+            action: |app| {
+                let targets = app.selected_container().map(|c| c.id.clone());
+                let confirm = app.config.confirm.stop;
+                start_action(
+                    app,
+                    ContainerAction::Stop,
+                    targets.into_iter().collect(),
+                    confirm,
+                );
+            },
+        },
+        MenuItem {
+            key: 'R',
+            label: "Restart project",
+            action: |app| {
+                let Some(project) = app
+                    .selected_container()
+                    .and_then(|container| container.compose_project())
+                    .map(str::to_string)
+                else {
+                    app.notification = Some("Not part of a compose project".to_string());
+                    app.mode = AppMode::Normal;
+                    return;
+                };
+                // Labels don't say how services depend on each other, so list order it is.
+                let targets = app
+                    .container_data
+                    .iter()
+                    .filter(|container| container.compose_project() == Some(project.as_str()))
+                    .map(|container| container.id.clone())
+                    .collect();
+                let confirm = app.config.confirm.restart_project;
+                start_action(app, ContainerAction::Restart, targets, confirm);
+            },
+        },
+        MenuItem {
+            key: 'p',
+            label: "Pin to top",
+            action: |app| {
+                app.toggle_pin();
+                app.mode = AppMode::Normal;
+            },
+        },
+        MenuItem {
+            key: 'w',
+            label: "Watch for exit",
+            action: |app| {
+                app.toggle_watch();
+                app.mode = AppMode::Normal;
+            },
+        },
+    ]
+}
+
+/// Plain keys taken by built-in bindings or menu items, so custom actions
+/// can't use them.
+pub fn reserved_keys() -> BTreeSet<char> {
+    let bindings = default_keybindings()
+        .into_iter()
+        .flat_map(|binding| binding.keys)
+        .filter_map(|key| match key.code {
+            KeyCode::Char(c) if (key.modifiers - KeyModifiers::SHIFT).is_empty() => Some(c),
+            _ => None,
+        });
+    bindings
+        .chain(menu_items().into_iter().map(|item| item.key))
+        .collect()
+}
+
+fn ctrl(c: char) -> KeyEvent {
+    KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL)
 }
 
 pub fn default_keybindings() -> Vec<KeyBinding> {
     vec![
         KeyBinding {
-            keys: vec![KeyCode::Esc, KeyCode::Char('q')],
+            // Raw mode swallows SIGINT, so Ctrl+C has to be handled here too.
+            keys: vec![ctrl('q'), ctrl('c')],
+            description: "Quit from any mode",
+            action: |app, _| {
+                app.stop_streams();
+                app.running = false;
+            },
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Esc.into(), KeyCode::Char('q').into()],
             description: "Quit / Close dialog",
             action: |app, _| match app.mode {
+                AppMode::Normal if app.table_filter.is_some() => app.table_filter = None,
                 AppMode::Normal => app.running = false,
+                AppMode::Logs if app.log_selection.is_some() => app.log_selection = None,
                 AppMode::Logs => {
-                    if let Some(handle) = app.log_task.take() {
-                        handle.abort();
-                    }
+                    app.remember_seen_logs();
+                    app.park_log_stream();
+                    app.close_stats();
+                    // Matches are log line indices, meaningless for the table.
+                    app.clear_search();
                     app.mode = AppMode::Normal;
                 }
                 AppMode::Search => {
+                    if app.last_mode == AppMode::Normal {
+                        app.table_filter = None;
+                    }
                     app.mode = app.last_mode;
-                    app.search_query.clear();
-                    app.search_matches.clear();
+                    app.clear_search();
                 }
-                AppMode::ContextMenu => {
+                AppMode::ContextMenu
+                | AppMode::Details
+                | AppMode::Labels
+                | AppMode::Events
+                | AppMode::Diagnostics => {
                     app.mode = AppMode::Normal;
                 }
+                AppMode::Rename => {
+                    app.mode = AppMode::Normal;
+                    app.rename_input.clear();
+                }
+                AppMode::ConfirmRemove => {
+                    app.mode = AppMode::Normal;
+                    app.confirm_input.clear();
+                }
+                AppMode::ConfirmAction => {
+                    app.mode = AppMode::Normal;
+                    app.confirm_action = None;
+                    app.action_targets.clear();
+                }
+                AppMode::QuickOpen => {
+                    app.mode = AppMode::Normal;
+                    app.quick_open_input.clear();
+                    app.selected = app.quick_open_from;
+                }
                 AppMode::Help => {
                     app.mode = app.last_mode;
                 }
+                AppMode::Resources if app.stats_only => {
+                    app.stop_streams();
+                    app.running = false;
+                }
                 AppMode::Resources => {
-                    if let Some(handle) = app.stats_task.take() {
-                        handle.abort();
-                    }
+                    app.close_stats();
                     app.mode = AppMode::Normal;
-                    app.cpu_data.clear();
-                    app.mem_data.clear();
                 }
             },
         },
         KeyBinding {
-            keys: vec![KeyCode::Up, KeyCode::Char('k')],
+            keys: vec![KeyCode::Up.into(), KeyCode::Char('k').into()],
             description: "Scroll up",
             action: |app, _| match app.mode {
-                AppMode::Normal => {
-                    app.selected = app.selected.saturating_sub(1);
-                }
+                AppMode::Normal => move_selection(app, false),
+                AppMode::Logs if app.log_selection.is_some() => move_log_cursor(app, false),
                 AppMode::Logs => {
                     app.user_scrolled = true;
                     app.vertical_scroll = app.vertical_scroll.saturating_sub(1);
                 }
+                AppMode::Labels => {
+                    app.vertical_scroll = app.vertical_scroll.saturating_sub(1);
+                }
                 AppMode::ContextMenu => {
-                    if app.menu_selected > 0 {
-                        app.menu_selected -= 1;
-                    } else {
-                        app.menu_selected = app.menu_items.len() - 1;
-                    }
+                    app.menu_selected = step(
+                        app.menu_selected,
+                        app.menu_len(),
+                        false,
+                        app.config.wrap_menu,
+                    );
                 }
                 _ => {}
             },
         },
         KeyBinding {
-            keys: vec![KeyCode::Down, KeyCode::Char('j')],
+            keys: vec![KeyCode::Down.into(), KeyCode::Char('j').into()],
             description: "Scroll down",
             action: |app, _| match app.mode {
-                AppMode::Normal => {
-                    app.selected = app.selected.saturating_add(1);
-                }
+                AppMode::Normal => move_selection(app, true),
+                AppMode::Logs if app.log_selection.is_some() => move_log_cursor(app, true),
                 AppMode::Logs => {
-                    app.user_scrolled = true;
                     app.vertical_scroll = app.vertical_scroll.saturating_add(1);
+                    app.follow_if_near_bottom();
+                }
+                AppMode::Labels => {
+                    let count = app
+                        .selected_container()
+                        .map_or(0, |container| container.labels.len());
+                    let last = count.saturating_sub(1) as u16;
+                    app.vertical_scroll = app.vertical_scroll.saturating_add(1).min(last);
                 }
                 AppMode::ContextMenu => {
-                    if app.menu_selected + 1 < app.menu_items.len() {
-                        app.menu_selected += 1;
-                    } else {
-                        app.menu_selected = 0;
-                    }
+                    app.menu_selected = step(
+                        app.menu_selected,
+                        app.menu_len(),
+                        true,
+                        app.config.wrap_menu,
+                    );
                 }
                 _ => {}
             },
         },
         KeyBinding {
-            keys: vec![KeyCode::Left, KeyCode::Char('h')],
+            keys: vec![KeyCode::Left.into(), KeyCode::Char('h').into()],
             description: "Scroll left",
             action: |app, _| {
                 if app.mode == AppMode::Logs {
+                    if app.wrap_logs {
+                        return;
+                    }
                     app.horizontal_scroll = app.horizontal_scroll.saturating_sub(10);
+                } else if let Some(input) = app.active_input_mut() {
+                    input.move_left();
                 }
             },
         },
         KeyBinding {
-            keys: vec![KeyCode::Right, KeyCode::Char('l')],
+            keys: vec![KeyCode::Right.into(), KeyCode::Char('l').into()],
             description: "Scroll right",
             action: |app, _| {
                 if app.mode == AppMode::Logs {
+                    if app.wrap_logs {
+                        return;
+                    }
                     app.horizontal_scroll = app.horizontal_scroll.saturating_add(10);
+                } else if let Some(input) = app.active_input_mut() {
+                    input.move_right();
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Delete.into()],
+            description: "Delete character under cursor",
+            action: |app, _| {
+                if let Some(input) = app.active_input_mut() {
+                    input.delete();
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Home.into()],
+            description: "Move cursor to start of input",
+            action: |app, _| {
+                if let Some(input) = app.active_input_mut() {
+                    input.move_home();
                 }
             },
         },
         KeyBinding {
-            keys: vec![KeyCode::Enter],
+            keys: vec![KeyCode::End.into()],
+            description: "Move cursor to end of input",
+            action: |app, _| {
+                if let Some(input) = app.active_input_mut() {
+                    input.move_end();
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Enter.into()],
             description: "Open / confirm",
             action: |app, _| match app.mode {
-                AppMode::Normal => {
-                    app.mode = AppMode::ContextMenu;
-                    app.menu_selected = 0;
-                }
-                AppMode::ContextMenu => match app.menu_selected {
-                    0 => {
-                        app.mode = AppMode::Logs;
-                        app.logs = vec!["Loading logs...".to_string()];
-                    }
-                    1 => {
-                        app.mode = AppMode::Resources;
-                    }
-                    2 => {
-                        app.mode = AppMode::Normal;
+                AppMode::Normal if app.selected_container().is_some() => {
+                    match app.config.enter_action {
+                        EnterAction::Menu => {
+                            app.mode = AppMode::ContextMenu;
+                            app.menu_selected = 0;
+                        }
+                        EnterAction::Logs => open_logs(app),
+                        EnterAction::Details => app.mode = AppMode::Details,
                     }
-                    _ => {}
-                },
+                }
+                AppMode::ContextMenu => activate_menu_item(app),
                 AppMode::Search => {
                     if app.last_mode == AppMode::Logs {
-                        app.search_matches = app
+                        let query = app.search_query.value();
+                        let scored: Vec<(usize, i64)> = app
                             .logs
                             .iter()
                             .enumerate()
-                            .filter(|(_, line)| line.contains(&app.search_query))
-                            .map(|(i, _)| i)
+                            .filter_map(|(i, line)| {
+                                Some((i, search::score(query, &strip_str(line), app.fuzzy_search)?))
+                            })
                             .collect();
-                        app.current_match_index = if app.search_matches.is_empty() {
-                            None
-                        } else {
-                            Some(0)
-                        };
+                        app.search_matches = scored.iter().map(|&(i, _)| i).collect();
+                        // Matches stay in log order for n/N; start at the best one.
+                        app.current_match_index = scored
+                            .iter()
+                            .enumerate()
+                            .max_by_key(|&(position, &(_, score))| {
+                                (score, std::cmp::Reverse(position))
+                            })
+                            .map(|(position, _)| position);
                         if let Some(index) = app.current_match_index {
-                            app.vertical_scroll = app.search_matches[index] as u16;
+                            app.center_log_line(app.search_matches[index]);
                         }
                         app.mode = AppMode::Logs;
                     } else {
-                        app.search_matches = app
-                            .container_data
-                            .iter()
-                            .enumerate()
-                            .filter(|(_, data)| data.1[1].contains(&app.search_query))
-                            .map(|(i, _)| i)
-                            .collect();
-                        app.current_match_index = if app.search_matches.is_empty() {
-                            None
-                        } else {
-                            Some(0)
-                        };
-                        if let Some(index) = app.current_match_index {
-                            app.selected = index;
+                        app.table_filter = app.container_filter().map(str::to_string);
+                        app.mode = AppMode::Normal;
+                        app.search_query.clear();
+                    }
+                }
+                AppMode::Rename => {
+                    let new_name = app.rename_input.value().trim().to_string();
+                    if is_valid_container_name(&new_name) {
+                        if let Some(container) = app.selected_container() {
+                            app.pending_rename = Some((container.id.clone(), new_name));
                         }
+                        app.rename_input.clear();
+                        app.mode = AppMode::Normal;
+                    } else {
+                        app.prompt_error = Some(format!("Invalid container name: {new_name:?}"));
+                    }
+                }
+                AppMode::ConfirmRemove => {
+                    let Some(container) = app.selected_container() else {
+                        return;
+                    };
+                    let name = container.names.trim_start_matches('/');
+                    let typed = app.confirm_input.value().trim();
+                    if typed == name || typed == "yes" {
+                        app.pending_remove = Some(container.id.clone());
+                        app.confirm_input.clear();
                         app.mode = AppMode::Normal;
+                    } else {
+                        app.prompt_error = Some(format!("Type {name:?} or \"yes\" to confirm"));
+                    }
+                }
+                AppMode::ConfirmAction => {
+                    let Some(action) = app.confirm_action else {
+                        return;
+                    };
+                    match app.timeout_input.value().trim().parse::<u32>() {
+                        Ok(timeout) => {
+                            let targets = std::mem::take(&mut app.action_targets);
+                            app.pending_action = Some((action, targets, timeout));
+                            app.confirm_action = None;
+                            app.mode = AppMode::Normal;
+                        }
+                        Err(_) => {
+                            app.prompt_error =
+                                Some("Timeout must be a whole number of seconds".to_string());
+                        }
                     }
                 }
+                AppMode::QuickOpen => {
+                    app.quick_open_input.clear();
+                    app.mode = AppMode::Normal;
+                }
                 _ => {}
             },
         },
         KeyBinding {
-            keys: vec![KeyCode::Backspace],
+            keys: vec![KeyCode::Backspace.into()],
             description: "Delete character in search",
             action: |app, _| {
-                if app.mode == AppMode::Search {
-                    app.search_query.pop();
+                if let Some(input) = app.active_input_mut() {
+                    input.backspace();
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![ctrl('d')],
+            description: "Scroll down half a page",
+            action: |app, _| {
+                if app.mode == AppMode::Logs {
+                    app.vertical_scroll = app.vertical_scroll.saturating_add(half_page(app));
+                    app.follow_if_near_bottom();
                 }
             },
         },
         KeyBinding {
-            keys: vec![KeyCode::Char('G')],
+            keys: vec![ctrl('u')],
+            description: "Scroll up half a page",
+            action: |app, _| {
+                if app.mode == AppMode::Logs {
+                    app.user_scrolled = true;
+                    app.vertical_scroll = app.vertical_scroll.saturating_sub(half_page(app));
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Tab.into()],
+            description: "Cycle the expanded resource graph",
+            action: |app, _| {
+                if app.shows_resources() {
+                    let stats = &app.config.stats;
+                    app.focused_metric = match app
+                        .focused_metric
+                        .and_then(|metric| stats.iter().position(|m| *m == metric))
+                    {
+                        Some(i) => stats.get(i + 1).copied(),
+                        None => stats.first().copied(),
+                    };
+                }
+            },
+        },
+        KeyBinding {
+            keys: ('1'..='9').map(|c| KeyCode::Char(c).into()).collect(),
+            description: "Expand the nth resource graph",
+            action: |app, key| {
+                if app.shows_resources()
+                    && let KeyCode::Char(c) = key.code
+                    && let Some(n) = c.to_digit(10)
+                    && let Some(&metric) = app.config.stats.get(n as usize - 1)
+                {
+                    app.focused_metric = Some(metric);
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Char('0').into()],
+            description: "Show all resource graphs",
+            action: |app, _| {
+                if app.shows_resources() {
+                    app.focused_metric = None;
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Char('B').into()],
+            description: "Capture the resource graphs as a baseline, or clear it",
+            action: |app, _| {
+                if app.shows_resources() {
+                    app.toggle_stats_baseline();
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Char('u').into()],
+            description: "Switch CPU % between per-core aggregate and normalized",
+            action: |app, _| {
+                if app.shows_resources() {
+                    app.config.cpu_scale = app.config.cpu_scale.toggled();
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Char('r').into()],
+            description: "Reconnect the log stream",
+            action: |app, _| {
+                if app.mode != AppMode::Logs {
+                    return;
+                }
+                if app.config.reconnect_clears_logs {
+                    open_logs(app);
+                } else {
+                    app.stop_log_stream();
+                    app.log_error = None;
+                    // Resuming right after the last line shown neither loses
+                    // nor repeats what was logged while disconnected.
+                    app.log_since = Some(app.last_log_time.unwrap_or_else(Utc::now));
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![ctrl('p')],
+            description: "Jump to a container by name",
+            action: |app, _| {
+                if app.mode == AppMode::Normal {
+                    app.quick_open_from = app.selected;
+                    app.quick_open_input.clear();
+                    app.mode = AppMode::QuickOpen;
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![ctrl('f')],
+            description: "Toggle fuzzy / literal search",
+            action: |app, _| app.fuzzy_search = !app.fuzzy_search,
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Char('G').into()],
             description: "Jump to latest log entry",
             action: |app, _| {
                 if app.mode == AppMode::Logs {
@@ -183,7 +595,7 @@ pub fn default_keybindings() -> Vec<KeyBinding> {
             },
         },
         KeyBinding {
-            keys: vec![KeyCode::Char('/')],
+            keys: vec![KeyCode::Char('/').into()],
             description: "Open search",
             action: |app, _| match app.mode {
                 AppMode::Logs => {
@@ -200,33 +612,252 @@ pub fn default_keybindings() -> Vec<KeyBinding> {
             },
         },
         KeyBinding {
-            keys: vec![KeyCode::Char('n')],
+            keys: vec![KeyCode::Char('n').into()],
             description: "Jump to next match",
-            action: |app, _| match app.mode {
-                AppMode::Logs => {
-                    jump_to_match(true, app);
-                }
-                AppMode::Normal => {
+            action: |app, _| {
+                if app.mode == AppMode::Logs {
                     jump_to_match(true, app);
                 }
-                _ => {}
             },
         },
         KeyBinding {
-            keys: vec![KeyCode::Char('N')],
+            keys: vec![KeyCode::Char('N').into()],
             description: "Jump to previous match",
-            action: |app, _| match app.mode {
-                AppMode::Logs => {
+            action: |app, _| {
+                if app.mode == AppMode::Logs {
                     jump_to_match(false, app);
                 }
+            },
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Char('+').into(), KeyCode::Char('=').into()],
+            description: "Grow overlay",
+            action: |app, _| {
+                if app.mode.has_overlay() {
+                    app.config.overlay.resize(OVERLAY_RESIZE_STEP);
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Char('-').into()],
+            description: "Shrink overlay",
+            action: |app, _| {
+                if app.mode.has_overlay() {
+                    app.config.overlay.resize(-OVERLAY_RESIZE_STEP);
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Char('c').into()],
+            description: "Clear search highlighting",
+            action: |app, _| {
+                if matches!(app.mode, AppMode::Normal | AppMode::Logs) {
+                    app.clear_search();
+                }
+                if app.mode == AppMode::Normal {
+                    app.table_filter = None;
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Char('p').into()],
+            description: "Open logs in $PAGER",
+            action: |app, _| {
+                if app.mode == AppMode::Logs {
+                    app.open_pager = true;
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Char('d').into()],
+            description: "Collapse duplicate log lines",
+            action: |app, _| {
+                if app.mode == AppMode::Logs {
+                    app.collapse_duplicates = !app.collapse_duplicates;
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Char('f').into()],
+            description: "Toggle the log line filter",
+            action: |app, _| {
+                if app.mode == AppMode::Logs && !app.config.log_filter.is_empty() {
+                    app.log_filter_enabled = !app.log_filter_enabled;
+                    // Filtered lines were never kept, so fetch the logs again.
+                    open_logs(app);
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![ctrl('l')],
+            description: "Clear the log buffer, keep streaming",
+            action: |app, _| {
+                if app.mode == AppMode::Logs && !app.logs_loading {
+                    app.logs.clear();
+                    app.log_selection = None;
+                    app.search_matches.clear();
+                    app.current_match_index = None;
+                    app.unread_from = None;
+                    app.hidden_log_lines = 0;
+                    app.logs_truncated = false;
+                    app.vertical_scroll = 0;
+                    app.horizontal_scroll = 0;
+                    app.user_scrolled = app.config.log_anchor == LogAnchor::Top;
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Char('w').into()],
+            description: "Wrap long log lines / scroll them sideways",
+            action: |app, _| {
+                if app.mode == AppMode::Logs {
+                    app.wrap_logs = !app.wrap_logs;
+                    app.horizontal_scroll = 0;
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Char('x').into()],
+            description: "Expand / cut short very long log lines",
+            action: |app, _| {
+                if app.mode == AppMode::Logs {
+                    app.expand_long_lines = !app.expand_long_lines;
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Char('S').into()],
+            description: "Show resource graphs beside the logs",
+            action: |app, _| {
+                if app.mode == AppMode::Logs {
+                    app.split_view = !app.split_view;
+                    if !app.split_view {
+                        app.close_stats();
+                    }
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Char('P').into()],
+            description: "Pause / resume container list refresh",
+            action: |app, _| {
+                app.paused = !app.paused;
+            },
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Char('a').into()],
+            description: "Toggle ANSI colors in logs",
+            action: |app, _| {
+                if app.mode == AppMode::Logs {
+                    app.ansi_colors = !app.ansi_colors;
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Char('J').into()],
+            description: "Format JSON log lines",
+            action: |app, _| {
+                if app.mode == AppMode::Logs {
+                    app.structured_logs = !app.structured_logs;
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Char('e').into()],
+            description: "Export full logs to a file",
+            action: |app, _| {
+                if app.mode == AppMode::Logs {
+                    app.export_logs = true;
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Char('y').into()],
+            description: "Copy a `docker logs -f` command, or the selected log lines",
+            action: |app, _| {
+                if app.mode == AppMode::Logs && app.log_selection.is_some() {
+                    copy_log_selection(app);
+                } else {
+                    copy_command(app, "docker logs -f", "");
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Char('Y').into()],
+            description: "Copy a `docker exec -it` command",
+            action: |app, _| copy_command(app, "docker exec -it", " sh"),
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Char('E').into()],
+            description: "Show recent Docker events",
+            action: |app, _| {
+                if app.mode == AppMode::Normal {
+                    app.mode = AppMode::Events;
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Char('!').into()],
+            description: "Show tugboat's own warnings and errors",
+            action: |app, _| {
+                if app.mode == AppMode::Normal {
+                    app.mode = AppMode::Diagnostics;
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Char('v').into()],
+            description: "Toggle log preview / select log lines to copy",
+            action: |app, _| match app.mode {
                 AppMode::Normal => {
-                    jump_to_match(false, app);
+                    app.show_preview = !app.show_preview;
+                    app.preview_for = None;
+                    app.preview_logs.clear();
                 }
+                AppMode::Logs if app.log_selection.is_some() => app.log_selection = None,
+                AppMode::Logs => start_log_selection(app),
                 _ => {}
             },
         },
         KeyBinding {
-            keys: vec![KeyCode::Char('?')],
+            keys: vec![KeyCode::Char('o').into()],
+            description: "Sort containers by name, newest or oldest",
+            action: |app, _| {
+                if app.mode == AppMode::Normal {
+                    app.cycle_sort();
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Char('m').into()],
+            description: "Toggle memory gauges",
+            action: |app, _| {
+                if app.mode == AppMode::Normal {
+                    app.show_memory = !app.show_memory;
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Char('s').into()],
+            description: "Toggle CPU sparklines",
+            action: |app, _| {
+                if app.mode == AppMode::Normal {
+                    app.show_cpu = !app.show_cpu;
+                    app.cpu_history.clear();
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Char('D').into()],
+            description: "Toggle dashboard summary",
+            action: |app, _| {
+                if app.mode == AppMode::Normal {
+                    app.show_summary = !app.show_summary;
+                }
+            },
+        },
+        KeyBinding {
+            keys: vec![KeyCode::Char('?').into()],
             description: "Open help",
             action: |app, _| {
                 app.last_mode = app.mode;
@@ -236,6 +867,174 @@ pub fn default_keybindings() -> Vec<KeyBinding> {
     ]
 }
 
+/// Opens the logs overlay from scratch; a stats stream beside it keeps running.
+fn open_logs(app: &mut AppState) {
+    app.stop_log_stream();
+    app.mode = AppMode::Logs;
+    app.logs = vec![LOADING_LOGS.to_string()];
+    app.log_selection = None;
+    app.logs_loading = true;
+    app.unread_from = None;
+    app.log_error = None;
+    app.log_since = None;
+    app.last_log_time = None;
+    app.log_throughput = Default::default();
+    app.hidden_log_lines = 0;
+    app.logs_truncated = false;
+    // Top-anchored logs count as scrolled so new lines don't pull the view down.
+    app.vertical_scroll = 0;
+    app.user_scrolled = app.config.log_anchor == LogAnchor::Top;
+    app.logs_for = app
+        .selected_container()
+        .map(|container| container.id.clone());
+    if let Some(id) = app.logs_for.clone()
+        && let Some(parked) = app.unpark_logs(&id)
+    {
+        app.logs = parked.logs;
+        app.logs_truncated = parked.truncated;
+        app.last_log_time = parked.last_log_time;
+        app.log_task = Some(parked.task);
+        app.logs_loading = false;
+        app.locate_unread();
+        if !app.user_scrolled {
+            app.vertical_scroll = app.logs.len().saturating_sub(app.visible_height as usize) as u16;
+        }
+    }
+}
+
+/// Queues `action` with the configured timeout, or with `confirm` first asks
+/// for the seconds it may wait before the containers are killed.
+fn start_action(app: &mut AppState, action: ContainerAction, targets: Vec<String>, confirm: bool) {
+    if targets.is_empty() {
+        app.mode = AppMode::Normal;
+        return;
+    }
+    if !confirm {
+        app.pending_action = Some((action, targets, app.config.stop_timeout_secs));
+        app.mode = AppMode::Normal;
+        return;
+    }
+    app.confirm_action = Some(action);
+    app.action_targets = targets;
+    app.timeout_input
+        .set(app.config.stop_timeout_secs.to_string());
+    app.mode = AppMode::ConfirmAction;
+}
+
+/// Runs the action of the highlighted context menu item.
+pub fn activate_menu_item(app: &mut AppState) {
+    if let Some(action) = app
+        .menu_items
+        .get(app.menu_selected)
+        .map(|item| item.action)
+    {
+        action(app);
+    } else {
+        queue_custom_action(app, app.menu_selected - app.menu_items.len());
+    }
+}
+
+/// Queues the `index`th configured action for the selected container.
+pub fn queue_custom_action(app: &mut AppState, index: usize) {
+    let (Some(action), Some(container)) = (app.config.actions.get(index), app.selected_container())
+    else {
+        return;
+    };
+    let command = action.command_for(&container.id, container.names.trim_start_matches('/'));
+    app.pending_command = Some((command, action.capture));
+    app.mode = AppMode::Normal;
+}
+
+fn copy_command(app: &mut AppState, prefix: &str, suffix: &str) {
+    if matches!(app.mode, AppMode::Normal | AppMode::Logs)
+        && let Some(container) = app.selected_container()
+    {
+        app.pending_copy = Some(format!("{prefix} {}{suffix}", container.id));
+    }
+}
+
+/// Starts a visual selection on the top line in view.
+fn start_log_selection(app: &mut AppState) {
+    if app.logs_loading || app.logs.is_empty() {
+        return;
+    }
+    // Collapsed lines stand for several, so they can't be picked one by one.
+    if app.collapse_duplicates {
+        app.notification = Some("Expand duplicate lines (d) to select lines".to_string());
+        return;
+    }
+    let line = (app.vertical_scroll as usize).min(app.logs.len() - 1);
+    app.log_selection = Some((line, line));
+    // New lines must not scroll the selection away.
+    app.user_scrolled = true;
+}
+
+/// Moves the end of the visual selection a line, scrolling to keep it in view.
+fn move_log_cursor(app: &mut AppState, down: bool) {
+    let Some((anchor, cursor)) = app.log_selection else {
+        return;
+    };
+    let last = app.logs.len().saturating_sub(1);
+    let cursor = if down {
+        (cursor + 1).min(last)
+    } else {
+        cursor.saturating_sub(1)
+    };
+    app.log_selection = Some((anchor, cursor));
+    let top = app.vertical_scroll as usize;
+    let height = (app.visible_height as usize).max(1);
+    if cursor < top {
+        app.vertical_scroll = cursor as u16;
+    } else if cursor >= top + height {
+        app.vertical_scroll = (cursor + 1 - height) as u16;
+    }
+}
+
+/// Queues the selected log lines, without escape sequences, for the clipboard.
+fn copy_log_selection(app: &mut AppState) {
+    let Some(range) = app.log_selection_range() else {
+        return;
+    };
+    let lines: Vec<String> = app.logs[range]
+        .iter()
+        .map(|line| strip_str(line).trim_end_matches(['\r', '\n']).to_string())
+        .collect();
+    app.pending_copy = Some(lines.join("\n"));
+    app.log_selection = None;
+}
+
+fn half_page(app: &AppState) -> u16 {
+    (app.visible_height / 2).max(1)
+}
+
+/// Moves `index` one step within `0..len`, either wrapping or clamping at the ends.
+fn step(index: usize, len: usize, forward: bool, wrap: bool) -> usize {
+    if len == 0 {
+        return 0;
+    }
+    match (forward, wrap) {
+        (true, true) => (index + 1) % len,
+        (true, false) => (index + 1).min(len - 1),
+        (false, true) => (index + len - 1) % len,
+        (false, false) => index.saturating_sub(1),
+    }
+}
+
+/// Steps the table selection over the rows the current filter shows.
+fn move_selection(app: &mut AppState, forward: bool) {
+    let visible = app.visible_containers();
+    let Some(position) = visible.iter().position(|&i| i == app.selected) else {
+        return;
+    };
+    let next = step(
+        position,
+        visible.len(),
+        forward,
+        app.config.wrap_container_list,
+    );
+    app.selected = visible[next];
+}
+
 fn jump_to_match(next: bool, app: &mut AppState) {
     if let Some(current) = app.current_match_index {
         if app.search_matches.is_empty() {
@@ -245,14 +1044,10 @@ fn jump_to_match(next: bool, app: &mut AppState) {
         app.current_match_index = if next {
             Some((current + 1) % len)
         } else {
-             Some((current + len - 1) % len)
+            Some((current + len - 1) % len)
         };
 
-        let new_index = app.search_matches[app.current_match_index.unwrap()] as u16;
-        if app.mode == AppMode::Logs {
-            app.vertical_scroll = new_index;
-        } else {
-            app.selected = new_index as usize;
-        }
+        let new_index = app.search_matches[app.current_match_index.unwrap()];
+        app.center_log_line(new_index);
     }
-} 
+}
@@ -0,0 +1,18 @@
+//! Docker container data and the terminal UI built on it. The `docker` module
+//! can be used on its own to list containers and stream their logs and stats.
+
+pub mod app;
+pub mod config;
+pub mod diagnostics;
+pub mod docker;
+pub mod keybindings;
+mod log_format;
+mod log_throughput;
+mod max_sliding_window;
+mod pager;
+mod search;
+mod shell;
+pub mod state;
+mod text_input;
+mod time_format;
+pub mod ui;
@@ -0,0 +1,216 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+use serde_json::{Map, Value};
+
+const TIMESTAMP_KEYS: [&str; 4] = ["time", "timestamp", "ts", "@timestamp"];
+const LEVEL_KEYS: [&str; 3] = ["level", "lvl", "severity"];
+const MESSAGE_KEYS: [&str; 3] = ["msg", "message", "log"];
+
+/// Renders a JSON log line as `timestamp LEVEL message key=value ...`.
+/// Returns `None` for lines that aren't a JSON object so callers can show them raw.
+pub fn format_structured(line: &str) -> Option<String> {
+    let trimmed = line.trim();
+    if !trimmed.starts_with('{') {
+        return None;
+    }
+    let Value::Object(mut fields) = serde_json::from_str::<Value>(trimmed).ok()? else {
+        return None;
+    };
+
+    let mut parts = Vec::new();
+    if let Some(timestamp) = take_first(&mut fields, &TIMESTAMP_KEYS) {
+        parts.push(timestamp);
+    }
+    if let Some(level) = take_first(&mut fields, &LEVEL_KEYS) {
+        parts.push(level.to_uppercase());
+    }
+    if let Some(message) = take_first(&mut fields, &MESSAGE_KEYS) {
+        parts.push(message);
+    }
+    parts.extend(
+        fields
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value_to_string(value))),
+    );
+    Some(parts.join(" "))
+}
+
+fn take_first(fields: &mut Map<String, Value>, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .find_map(|key| fields.remove(*key))
+        .map(|value| value_to_string(&value))
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Converts a line containing ANSI escape sequences into styled spans. SGR
+/// (color/formatting) sequences are applied, all other sequences are dropped.
+pub fn parse_ansi(line: &str) -> Line<'static> {
+    let mut spans = Vec::new();
+    let mut style = Style::default();
+    let mut text = String::new();
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            text.push(c);
+            continue;
+        }
+        if chars.next_if_eq(&'[').is_none() {
+            chars.next();
+            continue;
+        }
+        let mut params = String::new();
+        let mut command = None;
+        for c in chars.by_ref() {
+            if ('@'..='~').contains(&c) {
+                command = Some(c);
+                break;
+            }
+            params.push(c);
+        }
+        if command == Some('m') {
+            if !text.is_empty() {
+                spans.push(Span::styled(std::mem::take(&mut text), style));
+            }
+            style = apply_sgr(style, &params);
+        }
+    }
+    if !text.is_empty() {
+        spans.push(Span::styled(text, style));
+    }
+    Line::from(spans)
+}
+
+fn apply_sgr(mut style: Style, params: &str) -> Style {
+    let mut codes = params
+        .split(';')
+        .map(|code| code.parse::<u8>().unwrap_or(0));
+    while let Some(code) = codes.next() {
+        style = match code {
+            0 => Style::default(),
+            1 => style.add_modifier(Modifier::BOLD),
+            2 => style.add_modifier(Modifier::DIM),
+            3 => style.add_modifier(Modifier::ITALIC),
+            4 => style.add_modifier(Modifier::UNDERLINED),
+            7 => style.add_modifier(Modifier::REVERSED),
+            22 => style.remove_modifier(Modifier::BOLD | Modifier::DIM),
+            23 => style.remove_modifier(Modifier::ITALIC),
+            24 => style.remove_modifier(Modifier::UNDERLINED),
+            27 => style.remove_modifier(Modifier::REVERSED),
+            30..=37 => style.fg(base_color(code - 30)),
+            38 => match extended_color(&mut codes) {
+                Some(color) => style.fg(color),
+                None => style,
+            },
+            39 => style.fg(Color::Reset),
+            40..=47 => style.bg(base_color(code - 40)),
+            48 => match extended_color(&mut codes) {
+                Some(color) => style.bg(color),
+                None => style,
+            },
+            49 => style.bg(Color::Reset),
+            90..=97 => style.fg(bright_color(code - 90)),
+            100..=107 => style.bg(bright_color(code - 100)),
+            _ => style,
+        };
+    }
+    style
+}
+
+/// Parses the `5;n` (256 colors) or `2;r;g;b` (true color) tail of a 38/48 code.
+fn extended_color(codes: &mut impl Iterator<Item = u8>) -> Option<Color> {
+    match codes.next()? {
+        5 => Some(Color::Indexed(codes.next()?)),
+        2 => Some(Color::Rgb(codes.next()?, codes.next()?, codes.next()?)),
+        _ => None,
+    }
+}
+
+fn base_color(index: u8) -> Color {
+    [
+        Color::Black,
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::Gray,
+    ][index as usize]
+}
+
+fn bright_color(index: u8) -> Color {
+    [
+        Color::DarkGray,
+        Color::LightRed,
+        Color::LightGreen,
+        Color::LightYellow,
+        Color::LightBlue,
+        Color::LightMagenta,
+        Color::LightCyan,
+        Color::White,
+    ][index as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sgr_colors() {
+        let line = parse_ansi("\x1b[1;31mERROR\x1b[0m plain \x1b[38;5;42mindexed\x1b[39m");
+        assert_eq!(
+            Line::from(vec![
+                Span::styled(
+                    "ERROR",
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+                ),
+                Span::raw(" plain "),
+                Span::styled("indexed", Style::default().fg(Color::Indexed(42))),
+            ]),
+            line
+        );
+    }
+
+    #[test]
+    fn drops_non_sgr_sequences() {
+        let line = parse_ansi("\x1b[2Kclear\x1b[38;2;1;2;3mrgb");
+        assert_eq!(
+            Line::from(vec![
+                Span::raw("clear"),
+                Span::styled("rgb", Style::default().fg(Color::Rgb(1, 2, 3))),
+            ]),
+            line
+        );
+    }
+
+    #[test]
+    fn extracts_common_fields() {
+        let line = r#"{"level":"info","msg":"started","time":"12:00:01","port":8080}"#;
+        assert_eq!(
+            Some("12:00:01 INFO started port=8080".to_string()),
+            format_structured(line)
+        );
+    }
+
+    #[test]
+    fn supports_alternative_field_names() {
+        let line = "{\"@timestamp\":\"t\",\"severity\":\"warn\",\"message\":\"slow\"}\n";
+        assert_eq!(Some("t WARN slow".to_string()), format_structured(line));
+    }
+
+    #[test]
+    fn ignores_non_json_lines() {
+        assert_eq!(None, format_structured("plain text"));
+        assert_eq!(None, format_structured("{not json"));
+        assert_eq!(None, format_structured("[1, 2]"));
+    }
+}
@@ -0,0 +1,85 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// How far back the line rate looks.
+const RATE_WINDOW: Duration = Duration::from_secs(5);
+
+/// Totals and recent rate of what a log stream delivered, to spot containers
+/// that log excessively.
+#[derive(Debug, Default)]
+pub struct LogThroughput {
+    pub lines: usize,
+    pub bytes: usize,
+    started: Option<Instant>,
+    recent: VecDeque<(Instant, usize)>,
+}
+
+impl LogThroughput {
+    /// Counts lines that arrived as they were written.
+    pub fn record(&mut self, lines: usize, bytes: usize, now: Instant) {
+        self.record_backlog(lines, bytes, now);
+        self.recent.push_back((now, lines));
+        while let Some(&(at, _)) = self.recent.front()
+            && now.duration_since(at) > RATE_WINDOW
+        {
+            self.recent.pop_front();
+        }
+    }
+
+    /// Counts history fetched when the stream opened; it says nothing about
+    /// the current rate, so only the totals include it.
+    pub fn record_backlog(&mut self, lines: usize, bytes: usize, now: Instant) {
+        self.started.get_or_insert(now);
+        self.lines += lines;
+        self.bytes += bytes;
+    }
+
+    /// Lines per second over the last few seconds, or since the stream opened
+    /// if that is more recent.
+    pub fn lines_per_sec(&self, now: Instant) -> f64 {
+        let Some(started) = self.started else {
+            return 0.0;
+        };
+        let window = now
+            .duration_since(started)
+            .clamp(Duration::from_secs(1), RATE_WINDOW);
+        let recent: usize = self
+            .recent
+            .iter()
+            .filter(|(at, _)| now.duration_since(*at) <= RATE_WINDOW)
+            .map(|(_, lines)| lines)
+            .sum();
+        recent as f64 / window.as_secs_f64()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backlog_counts_toward_totals_only() {
+        let start = Instant::now();
+        let mut throughput = LogThroughput::default();
+        throughput.record_backlog(2000, 80_000, start);
+        assert_eq!(2000, throughput.lines);
+        assert_eq!(80_000, throughput.bytes);
+        assert_eq!(0.0, throughput.lines_per_sec(start));
+    }
+
+    #[test]
+    fn rate_covers_the_recent_window() {
+        let start = Instant::now();
+        let mut throughput = LogThroughput::default();
+        throughput.record(10, 100, start);
+        for second in 1..=5 {
+            throughput.record(10, 100, start + Duration::from_secs(second));
+        }
+        let now = start + Duration::from_secs(5);
+        assert_eq!(60, throughput.lines);
+        assert_eq!(12.0, throughput.lines_per_sec(now));
+        assert_eq!(6.0, throughput.lines_per_sec(now + Duration::from_secs(3)));
+    }
+}
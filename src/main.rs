@@ -1,26 +1,70 @@
-mod app;
-mod docker;
-mod keybindings;
-mod max_sliding_window;
-mod ui;
+mod cli;
 
-use std::{error::Error, sync::Arc};
+use std::{error::Error, io, sync::Arc};
 
-use app::AppState;
-use docker::get_container_data;
+use bollard::Docker;
+use clap::Parser;
+use cli::Cli;
 use tokio::sync::RwLock;
+use tugboat::{
+    app::{AppMode, AppState},
+    config::Config,
+    diagnostics::{Diagnostics, Level},
+    docker::{describe_error, get_container_data, write_logs},
+    state::SavedState,
+    ui,
+};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
-    let container_data = get_container_data().await?;
-    let app_state = Arc::new(RwLock::new(AppState {
-        container_data,
+    let cli = Cli::parse();
+    if let Some(container) = &cli.logs {
+        let docker = Docker::connect_with_socket_defaults().map_err(|e| describe_error(&e))?;
+        write_logs(
+            &docker,
+            container,
+            cli.tail,
+            cli.raw,
+            &mut io::stdout().lock(),
+        )
+        .await?;
+        return Ok(());
+    }
+    let config = Config::load()?;
+
+    let mut saved = SavedState::load();
+    let container_data = get_container_data(cli.label.as_deref(), false).await?;
+    let mut app = AppState {
+        label_filter: cli.label,
+        config,
+        pinned: saved.pinned.iter().cloned().collect(),
+        diagnostics: Diagnostics::new(Level::from_verbosity(cli.verbose)),
         ..Default::default()
-    }));
+    };
+    // Sorts the list like every later refresh will.
+    app.set_container_data(container_data);
+    if let Some(query) = &cli.stats {
+        app.selected = app
+            .find_container(query)
+            .ok_or_else(|| format!("No container matches {query}"))?;
+        app.mode = AppMode::Resources;
+        app.stats_only = true;
+    } else if let Some(id) = &saved.selected_id {
+        app.select_container_id(id);
+    }
+    let app_state = Arc::new(RwLock::new(app));
 
-    ui::start_ui(app_state)
+    ui::start_ui(app_state.clone())
         .await
         .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
 
+    app_state.read().await.save_into(&mut saved);
+    if let Err(e) = saved.save() {
+        eprintln!(
+            "Couldn't save {}: {e}",
+            SavedState::path().unwrap_or_default().display()
+        );
+    }
+
     Ok(())
 }
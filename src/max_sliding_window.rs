@@ -2,7 +2,7 @@ use std::collections::VecDeque;
 
 use smart_default::SmartDefault;
 
-#[derive(SmartDefault)]
+#[derive(Clone, SmartDefault)]
 pub struct MaxSlidingWindow<T> {
     pub data: VecDeque<(T, T)>,
     max_queue: VecDeque<T>,
@@ -30,10 +30,10 @@ where
     }
 
     fn remove(&mut self) {
-        if let Some(front) = self.data.pop_front() {
-            if Some(&front.1) == self.max_queue.front() {
-                self.max_queue.pop_front();
-            }
+        if let Some(front) = self.data.pop_front()
+            && Some(&front.1) == self.max_queue.front()
+        {
+            self.max_queue.pop_front();
         }
     }
 
@@ -0,0 +1,100 @@
+use std::{
+    env,
+    io::{self, Write},
+    path::PathBuf,
+    process::Command,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use strip_ansi_escapes::strip_str;
+use tempfile::NamedTempFile;
+
+const DEFAULT_PAGER: &str = "less";
+
+pub fn save_logs(out: &mut impl Write, logs: &[String]) -> io::Result<()> {
+    for line in logs {
+        writeln!(out, "{}", strip_str(line).trim_end_matches('\n'))?;
+    }
+    Ok(())
+}
+
+/// A new temp file such as `tugboat-0123456789ab-x7Gq2a.log`. The random part
+/// and exclusive creation keep other users of a shared temp dir from planting
+/// a file or symlink at the path beforehand. It's deleted when dropped.
+pub fn temp_log_file(container_id: &str) -> io::Result<NamedTempFile> {
+    let short_id = &container_id[..container_id.len().min(12)];
+    tempfile::Builder::new()
+        .prefix(&format!("tugboat-{}-", short_id))
+        .suffix(".log")
+        .tempfile()
+}
+
+/// File in the working directory for a full log export, e.g. `tugboat-web-1700000000.log`.
+pub fn export_log_path(container_name: &str) -> PathBuf {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let name = container_name
+        .trim_start_matches('/')
+        .replace(['/', ','], "_");
+    PathBuf::from(format!("tugboat-{}-{}.log", name, timestamp))
+}
+
+fn pager_command() -> String {
+    env::var("PAGER")
+        .ok()
+        .filter(|pager| !pager.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_PAGER.to_string())
+}
+
+/// Writes the logs to a temp file and blocks until `$PAGER` (or `less`) exits.
+/// The caller is responsible for suspending and restoring the terminal.
+pub fn open_in_pager(container_id: &str, logs: &[String]) -> io::Result<()> {
+    let mut file = temp_log_file(container_id)?;
+    save_logs(file.as_file_mut(), logs)?;
+    file.as_file_mut().flush()?;
+
+    let pager = pager_command();
+    let mut parts = pager.split_whitespace();
+    let program = parts.next().unwrap_or(DEFAULT_PAGER);
+    let status = Command::new(program).args(parts).arg(file.path()).status();
+    status.map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_logs_writes_one_line_per_entry() {
+        let logs = vec!["first\n".to_string(), "second".to_string()];
+        let mut out = Vec::new();
+
+        save_logs(&mut out, &logs).unwrap();
+
+        assert_eq!("first\nsecond\n", String::from_utf8(out).unwrap());
+    }
+
+    #[test]
+    fn export_log_path_sanitizes_name() {
+        let path = export_log_path("/web/app");
+        let name = path.to_str().unwrap();
+        assert!(name.starts_with("tugboat-web_app-"));
+        assert!(name.ends_with(".log"));
+    }
+
+    #[test]
+    fn temp_log_files_are_unique_and_removed() {
+        let first = temp_log_file("0123456789abcdef").unwrap();
+        let second = temp_log_file("0123456789abcdef").unwrap();
+        let name = first.path().file_name().unwrap().to_str().unwrap();
+        assert!(name.starts_with("tugboat-0123456789ab-"));
+        assert!(name.ends_with(".log"));
+        assert_ne!(first.path(), second.path());
+
+        let path = first.path().to_path_buf();
+        drop(first);
+        assert!(!path.exists());
+    }
+}
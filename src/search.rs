@@ -0,0 +1,71 @@
+//! Matching for container and log search: fuzzy subsequence matching with
+//! scores (so `wb` finds `web`), or literal substring matching as a fallback.
+
+use std::sync::LazyLock;
+
+use fuzzy_matcher::{FuzzyMatcher, skim::SkimMatcherV2};
+
+static MATCHER: LazyLock<SkimMatcherV2> = LazyLock::new(SkimMatcherV2::default);
+
+/// How well `text` matches `query`, higher is better; `None` if it doesn't.
+pub fn score(query: &str, text: &str, fuzzy: bool) -> Option<i64> {
+    if fuzzy {
+        MATCHER.fuzzy_match(text, query)
+    } else {
+        text.contains(query).then_some(0)
+    }
+}
+
+/// Char indices of the characters in `text` that `query` matched. Literal
+/// queries mark every occurrence, not just the first.
+pub fn match_indices(query: &str, text: &str, fuzzy: bool) -> Option<Vec<usize>> {
+    if fuzzy {
+        MATCHER
+            .fuzzy_indices(text, query)
+            .map(|(_, indices)| indices)
+    } else {
+        let len = query.chars().count();
+        let indices: Vec<usize> = text
+            .match_indices(query)
+            .flat_map(|(byte, _)| {
+                let start = text[..byte].chars().count();
+                start..start + len
+            })
+            .collect();
+        (!indices.is_empty()).then_some(indices)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuzzy_matches_subsequences() {
+        assert!(score("wb", "web", true).is_some());
+        assert!(score("bw", "web", true).is_none());
+        assert!(score("wb", "web", false).is_none());
+    }
+
+    #[test]
+    fn tighter_matches_score_higher() {
+        let tight = score("web", "web-frontend", true).unwrap();
+        let loose = score("web", "worker-backend", true).unwrap();
+        assert!(tight > loose);
+    }
+
+    #[test]
+    fn literal_indices_count_chars() {
+        assert_eq!(Some(vec![3, 4]), match_indices("rr", "é error", false));
+        assert_eq!(Some(vec![0, 2]), match_indices("wb", "web", true));
+    }
+
+    #[test]
+    fn literal_indices_cover_every_occurrence() {
+        assert_eq!(
+            Some(vec![0, 1, 8, 9]),
+            match_indices("ab", "ab then ab", false)
+        );
+        assert_eq!(None, match_indices("", "web", false));
+    }
+}
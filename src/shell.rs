@@ -0,0 +1,56 @@
+use std::{
+    io::{self, Write},
+    process::Command,
+};
+
+fn sh(command: &str) -> Command {
+    let mut sh = Command::new("sh");
+    sh.arg("-c").arg(command);
+    sh
+}
+
+/// Runs `command` on the real terminal, then waits for Enter so its output can
+/// be read before the UI comes back. The caller suspends the UI around this.
+pub fn run_in_terminal(command: &str) -> io::Result<()> {
+    let status = sh(command).status()?;
+    print!("\n[{status}] Press Enter to return to tugboat");
+    io::stdout().flush()?;
+    io::stdin().read_line(&mut String::new())?;
+    Ok(())
+}
+
+/// Runs `command` to completion and sums it up in one line: the last line it
+/// printed, or its exit status if it printed nothing.
+pub fn capture_output(command: &str) -> String {
+    let output = match sh(command).output() {
+        Ok(output) => output,
+        Err(e) => return format!("Failed to run {command}: {e}"),
+    };
+    let text = [output.stdout, output.stderr].concat();
+    let text = String::from_utf8_lossy(&text);
+    let last_line = text.lines().rev().find(|line| !line.trim().is_empty());
+    match (last_line, output.status.success()) {
+        (Some(line), true) => line.trim().to_string(),
+        (Some(line), false) => format!("{} ({})", line.trim(), output.status),
+        (None, _) => format!("{command}: {}", output.status),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capture_keeps_the_last_line() {
+        assert_eq!("two", capture_output("printf 'one\\ntwo\\n\\n'"));
+    }
+
+    #[test]
+    fn capture_reports_failures() {
+        assert_eq!(
+            "nope (exit status: 3)",
+            capture_output("echo nope >&2; exit 3")
+        );
+        assert_eq!("true: exit status: 0", capture_output("true"));
+    }
+}
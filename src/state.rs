@@ -0,0 +1,62 @@
+use std::{collections::BTreeSet, fs, io, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// What tugboat remembers between runs on its own, as opposed to the config
+/// the user writes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SavedState {
+    /// Container selected when tugboat last quit.
+    pub selected_id: Option<String>,
+    /// Ids of the containers kept at the top of the list.
+    pub pinned: BTreeSet<String>,
+}
+
+impl SavedState {
+    pub fn path() -> Option<PathBuf> {
+        dirs::state_dir()
+            .or_else(dirs::data_local_dir)
+            .map(|dir| dir.join("tugboat").join("state.toml"))
+    }
+
+    /// Loads the state file. It's only a convenience, so a missing or
+    /// unreadable one starts over with nothing remembered.
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| Self::parse(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let contents = toml::to_string(self).map_err(io::Error::other)?;
+        fs::write(path, contents)
+    }
+
+    pub fn parse(contents: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_toml() {
+        let state = SavedState {
+            selected_id: Some("abc123".into()),
+            pinned: BTreeSet::from(["def456".to_string()]),
+        };
+        let contents = toml::to_string(&state).unwrap();
+        assert_eq!(state, SavedState::parse(&contents).unwrap());
+        assert_eq!(SavedState::default(), SavedState::parse("").unwrap());
+    }
+}
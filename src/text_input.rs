@@ -0,0 +1,153 @@
+/// A single-line text buffer with a cursor, shared by search and prompt modes.
+/// The cursor is a char index so multi-byte input is edited correctly.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TextInput {
+    value: String,
+    cursor: usize,
+}
+
+impl TextInput {
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// The text left of the cursor, used to place the terminal cursor when rendering.
+    pub fn before_cursor(&self) -> &str {
+        &self.value[..self.byte_index()]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    pub fn set(&mut self, value: impl Into<String>) {
+        self.value = value.into();
+        self.cursor = self.value.chars().count();
+    }
+
+    pub fn clear(&mut self) {
+        self.value.clear();
+        self.cursor = 0;
+    }
+
+    pub fn insert(&mut self, c: char) {
+        let index = self.byte_index();
+        self.value.insert(index, c);
+        self.cursor += 1;
+    }
+
+    /// Removes the character before the cursor.
+    pub fn backspace(&mut self) {
+        if self.cursor == 0 {
+            return;
+        }
+        self.cursor -= 1;
+        let index = self.byte_index();
+        self.value.remove(index);
+    }
+
+    /// Removes the character under the cursor.
+    pub fn delete(&mut self) {
+        if self.cursor < self.value.chars().count() {
+            let index = self.byte_index();
+            self.value.remove(index);
+        }
+    }
+
+    pub fn move_left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    pub fn move_right(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.value.chars().count());
+    }
+
+    pub fn move_home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn move_end(&mut self) {
+        self.cursor = self.value.chars().count();
+    }
+
+    fn byte_index(&self) -> usize {
+        self.value
+            .char_indices()
+            .nth(self.cursor)
+            .map(|(i, _)| i)
+            .unwrap_or(self.value.len())
+    }
+}
+
+impl From<&str> for TextInput {
+    fn from(value: &str) -> Self {
+        let mut input = Self::default();
+        input.set(value);
+        input
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_appends_at_cursor() {
+        let mut input = TextInput::default();
+        input.insert('a');
+        input.insert('c');
+        input.move_left();
+        input.insert('b');
+        assert_eq!("abc", input.value());
+    }
+
+    #[test]
+    fn backspace_removes_before_cursor() {
+        let mut input = TextInput::from("abcd");
+        input.move_left();
+        input.move_left();
+        input.backspace();
+        assert_eq!("acd", input.value());
+    }
+
+    #[test]
+    fn delete_removes_under_cursor() {
+        let mut input = TextInput::from("abcd");
+        input.move_home();
+        input.move_right();
+        input.delete();
+        assert_eq!("acd", input.value());
+        assert_eq!("a", input.before_cursor());
+        input.move_end();
+        input.delete();
+        assert_eq!("acd", input.value());
+    }
+
+    #[test]
+    fn movement_is_clamped() {
+        let mut input = TextInput::from("ab");
+        input.move_right();
+        input.insert('c');
+        assert_eq!("abc", input.value());
+        input.move_home();
+        input.move_left();
+        input.backspace();
+        assert_eq!("abc", input.value());
+        input.insert('x');
+        input.move_end();
+        input.insert('y');
+        assert_eq!("xabcy", input.value());
+    }
+
+    #[test]
+    fn handles_multibyte_characters() {
+        let mut input = TextInput::from("héllo");
+        input.move_home();
+        input.move_right();
+        input.move_right();
+        input.backspace();
+        assert_eq!("hllo", input.value());
+        input.insert('ë');
+        assert_eq!("hëllo", input.value());
+    }
+}
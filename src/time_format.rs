@@ -0,0 +1,77 @@
+//! The one place times are formatted for display, so every view follows the
+//! configured format and time zone.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::{
+    DateTime, Local, Utc,
+    format::{Item, StrftimeItems},
+};
+
+use crate::config::TimeZone;
+
+/// Unix time `secs` in `zone`, formatted with the user's `strftime`-style
+/// `format`; an invalid format shows as such rather than panicking mid-draw.
+pub fn format_timestamp(secs: i64, format: &str, zone: TimeZone) -> String {
+    match zone {
+        TimeZone::Local => format_in(secs, format, &Local),
+        TimeZone::Utc => format_in(secs, format, &Utc),
+    }
+}
+
+pub fn format_system_time(at: SystemTime, format: &str, zone: TimeZone) -> String {
+    let secs = match at.duration_since(UNIX_EPOCH) {
+        Ok(elapsed) => elapsed.as_secs() as i64,
+        Err(e) => -(e.duration().as_secs() as i64),
+    };
+    format_timestamp(secs, format, zone)
+}
+
+fn format_in<Tz: chrono::TimeZone>(secs: i64, format: &str, tz: &Tz) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let items: Vec<Item> = StrftimeItems::new(format).collect();
+    if items.contains(&Item::Error) {
+        return format!("invalid format {format:?}");
+    }
+    match DateTime::from_timestamp(secs, 0) {
+        Some(at) => at
+            .with_timezone(tz)
+            .format_with_items(items.iter())
+            .to_string(),
+        None => "?".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn formats_timestamps_with_the_configured_format() {
+        assert_eq!(
+            "2023-11-14 22:13",
+            format_timestamp(1_700_000_000, "%Y-%m-%d %H:%M", TimeZone::Utc)
+        );
+        assert_eq!(
+            "invalid format \"%Y %\"",
+            format_timestamp(1_700_000_000, "%Y %", TimeZone::Utc)
+        );
+    }
+
+    #[test]
+    fn formats_system_times() {
+        let at = UNIX_EPOCH + Duration::from_secs(2 * 24 * 60 * 60 + 3723);
+        assert_eq!(
+            "01:02:03",
+            format_system_time(at, "%H:%M:%S", TimeZone::Utc)
+        );
+        assert_eq!(
+            "01:02 AM",
+            format_system_time(at, "%I:%M %p", TimeZone::Utc)
+        );
+    }
+}
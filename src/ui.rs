@@ -1,31 +1,49 @@
 use std::{
-    io::{self},
-    time::Duration,
+    io::{self, Write},
+    time::{Duration, Instant},
     vec,
 };
 
+use arboard::Clipboard;
+use bollard::Docker as BollardDocker;
 use ratatui::{
     Frame, Terminal,
     crossterm::{
-        event::{self, DisableMouseCapture, EnableMouseCapture, Event},
+        event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyEvent, KeyModifiers},
         execute,
         terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
     },
-    layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
+    layout::{Alignment, Constraint, Direction, Flex, Layout, Margin, Rect},
     prelude::CrosstermBackend,
     style::{Color, Modifier, Style},
     symbols,
     text::{Line, Span},
     widgets::{
-        Axis, Block, Borders, Cell, Chart, Clear, Dataset, List, ListItem, ListState, Paragraph,
-        Row, Scrollbar, ScrollbarState, Table, Wrap,
+        Axis, Block, Borders, Cell, Chart, Clear, Dataset, LineGauge, List, ListItem, ListState,
+        Paragraph, Row, Scrollbar, ScrollbarState, Table, Wrap,
     },
 };
 
+use strip_ansi_escapes::strip_str;
+use tokio::task::JoinHandle;
+
 use crate::{
     app::{AppMode, AppState, SharedState},
-    docker::{get_container_data, stream_logs, stream_stats},
+    config::{ContainerSort, CpuScale, GraphPalette, Metric, OverlaySize},
+    diagnostics::Level,
+    docker::{
+        ContainerInfo, MemoryUsage, NO_IP, export_logs, fetch_menu_usage, fetch_preview,
+        get_container_data, remove_container, rename_container, run_container_action, stream_logs,
+        stream_stats, truncate, watch_events,
+    },
     keybindings::default_keybindings,
+    log_format::{format_structured, parse_ansi},
+    max_sliding_window::MaxSlidingWindow,
+    pager::{export_log_path, open_in_pager},
+    search,
+    shell::{capture_output, run_in_terminal},
+    text_input::TextInput,
+    time_format::{format_system_time, format_timestamp},
 };
 
 pub async fn start_ui(app_state: SharedState) -> Result<(), io::Error> {
@@ -35,14 +53,39 @@ pub async fn start_ui(app_state: SharedState) -> Result<(), io::Error> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
     let mut visible_height = None;
+    let mut clipboard = None;
+    // Tasks that change something (files, containers) and should finish before exit.
+    let mut action_tasks: Vec<JoinHandle<()>> = Vec::new();
+    let events_task = watch_events(app_state.clone());
+    {
+        // Launching straight into the resources view needs its stream up front.
+        let mut app = app_state.write().await;
+        if app.needs_stats_stream()
+            && let Some(container_id) = selected_id(&app)
+        {
+            app.stats_task = Some(stream_stats(container_id, app_state.clone()));
+        }
+    }
 
     loop {
+        if std::mem::take(&mut app_state.write().await.ring_bell) {
+            let backend = terminal.backend_mut();
+            backend.write_all(b"\x07")?;
+            backend.flush()?;
+        }
+        if let Some(message) = app_state.write().await.desktop_alert.take() {
+            let backend = terminal.backend_mut();
+            write!(backend, "\x1b]9;tugboat: {message}\x07")?;
+            backend.flush()?;
+        }
+        let poll_timeout;
         {
             let app = app_state.read().await;
+            poll_timeout = Duration::from_millis(app.config.poll_timeout_ms);
             terminal.draw(|f| {
                 draw_ui(f, &app);
                 let area = f.area();
-                let overlay_area = centered_rect(80, 80, area);
+                let overlay_area = overlay_rect(app.config.overlay, area);
                 visible_height = Some(overlay_area.height.saturating_sub(2));
             })?;
             if !app.running {
@@ -50,28 +93,180 @@ pub async fn start_ui(app_state: SharedState) -> Result<(), io::Error> {
             }
         }
 
-        if event::poll(Duration::from_millis(200))? {
+        if event::poll(poll_timeout)? {
             let mut app = app_state.write().await;
-            let container_data = get_container_data().await;
-            app.container_data = container_data.unwrap_or(Vec::new());
+            if !app.paused {
+                let result =
+                    get_container_data(app.label_filter.as_deref(), app.samples_usage()).await;
+                app.apply_refresh(result.map_err(|e| e.to_string()));
+            }
             if let Event::Key(key_event) = event::read()? {
-                app.handle_input(key_event.code);
-                if app.mode == AppMode::Logs && app.logs == vec!["Loading logs...".to_string()] {
+                app.handle_input(key_event);
+                if app.needs_log_stream()
+                    && let Some(container) = app.selected_container()
+                {
+                    let container_id = container.id.clone();
+                    let follow = container.is_running();
                     app.visible_height = visible_height.unwrap_or(1);
 
-                    let container_id = app.container_data[app.selected].0.clone();
-                    let log_task = stream_logs(container_id, app_state.clone());
+                    let since = app.log_since.take();
+                    let log_task = stream_logs(container_id, follow, since, app_state.clone());
                     app.log_task = Some(log_task);
                 }
-                if app.mode == AppMode::Resources {
-                    let container_id = app.container_data[app.selected].0.clone();
+                if let Some(container_id) = app.preview_target() {
+                    if let Some(handle) = app.preview_task.take() {
+                        handle.abort();
+                    }
+                    app.preview_logs.clear();
+                    app.preview_for = Some(container_id.clone());
+                    app.preview_task = Some(fetch_preview(container_id, app_state.clone()));
+                }
+                if app.mode != AppMode::ContextMenu && app.menu_usage_for.is_some() {
+                    app.clear_menu_usage();
+                }
+                if let Some(container_id) = app.menu_usage_target() {
+                    app.clear_menu_usage();
+                    app.menu_usage_for = Some(container_id.clone());
+                    app.menu_usage_task = Some(fetch_menu_usage(container_id, app_state.clone()));
+                }
+                if let Some((container_id, new_name)) = app.pending_rename.take() {
+                    app.notification =
+                        Some(match rename_container(&container_id, &new_name).await {
+                            Ok(()) => format!("Renamed container to {new_name}"),
+                            Err(e) => format!("Rename failed: {e}"),
+                        });
+                    let result =
+                        get_container_data(app.label_filter.as_deref(), app.samples_usage()).await;
+                    app.apply_refresh(result.map_err(|e| e.to_string()));
+                }
+                if let Some(container_id) = app.pending_remove.take() {
+                    app.notification = Some(match remove_container(&container_id).await {
+                        Ok(()) => "Removed container".to_string(),
+                        Err(e) => format!("Remove failed: {e}"),
+                    });
+                    let result =
+                        get_container_data(app.label_filter.as_deref(), app.samples_usage()).await;
+                    app.apply_refresh(result.map_err(|e| e.to_string()));
+                }
+                if let Some((action, container_ids, timeout)) = app.pending_action.take() {
+                    // Docker only answers once the container is down, up to `timeout` seconds later.
+                    app.notification = Some(format!(
+                        "{} requested, SIGKILL after {timeout}s",
+                        action.label()
+                    ));
+                    let app_state = app_state.clone();
+                    action_tasks.retain(|task| !task.is_finished());
+                    action_tasks.push(tokio::spawn(async move {
+                        let mut failures = Vec::new();
+                        for container_id in &container_ids {
+                            let result = run_container_action(action, container_id, timeout)
+                                .await
+                                .map_err(|e| e.to_string());
+                            let mut app = app_state.write().await;
+                            match result {
+                                Ok(()) => app.diagnostics.info(format!(
+                                    "{} {container_id} with a {timeout}s timeout",
+                                    action.past_tense()
+                                )),
+                                Err(e) => {
+                                    app.diagnostics.warn(format!(
+                                        "{} of {container_id} failed: {e}",
+                                        action.label()
+                                    ));
+                                    failures.push(e);
+                                }
+                            }
+                        }
+                        app_state.write().await.notification =
+                            Some(match (failures.first(), container_ids.len()) {
+                                (None, 1) => format!("{} container", action.past_tense()),
+                                (None, n) => format!("{} {n} containers", action.past_tense()),
+                                (Some(e), 1) => format!("{} failed: {e}", action.label()),
+                                (Some(e), n) => format!(
+                                    "{} failed for {} of {n} containers: {e}",
+                                    action.label(),
+                                    failures.len()
+                                ),
+                            });
+                    }));
+                }
+                // Commands that take over the terminal run after the other
+                // handlers, which would otherwise wait for the next key.
+                let mut terminal_command = None;
+                if let Some((command, capture)) = app.pending_command.take() {
+                    if capture {
+                        app.notification = Some(format!("Running {command}..."));
+                        let app_state = app_state.clone();
+                        action_tasks.retain(|task| !task.is_finished());
+                        action_tasks.push(tokio::spawn(async move {
+                            let message =
+                                tokio::task::spawn_blocking(move || capture_output(&command))
+                                    .await
+                                    .unwrap_or_else(|e| format!("Action failed: {e}"));
+                            app_state.write().await.notification = Some(message);
+                        }));
+                    } else {
+                        terminal_command = Some(command);
+                    }
+                }
+                if let Some(text) = app.pending_copy.take() {
+                    app.notification = Some(match copy_to_clipboard(&mut clipboard, &text) {
+                        Ok(()) => match text.lines().count() {
+                            0 | 1 => format!("Copied: {text}"),
+                            n => format!("Copied {n} lines"),
+                        },
+                        Err(e) => format!("Copy failed: {e}"),
+                    });
+                }
+                if app.export_logs
+                    && let Some(container) = app.selected_container()
+                {
+                    let container_id = container.id.clone();
+                    let path = export_log_path(&container.names);
+                    app.export_logs = false;
+                    app.notification = Some(format!("Exporting logs to {}...", path.display()));
+                    let app_state = app_state.clone();
+                    action_tasks.retain(|task| !task.is_finished());
+                    action_tasks.push(tokio::spawn(async move {
+                        let exported = match BollardDocker::connect_with_socket_defaults() {
+                            Ok(docker) => export_logs(&docker, &container_id, &path).await,
+                            Err(e) => Err(e.into()),
+                        };
+                        let message = match exported.map_err(|e| e.to_string()) {
+                            Ok(()) => format!("Exported logs to {}", path.display()),
+                            Err(e) => format!("Log export failed: {e}"),
+                        };
+                        app_state.write().await.notification = Some(message);
+                    }));
+                }
+                if app.needs_stats_stream()
+                    && let Some(container_id) = selected_id(&app)
+                {
                     let stats_task = stream_stats(container_id, app_state.clone());
                     app.stats_task = Some(stats_task);
                 }
+                let pager_logs = match selected_id(&app) {
+                    Some(container_id) if app.open_pager => {
+                        app.open_pager = false;
+                        Some((container_id, app.logs.clone()))
+                    }
+                    _ => None,
+                };
+                drop(app);
+                if let Some(command) = terminal_command {
+                    run_suspended(&mut terminal, || run_in_terminal(&command))?;
+                }
+                if let Some((container_id, logs)) = pager_logs {
+                    run_suspended(&mut terminal, || open_in_pager(&container_id, &logs))?;
+                }
             }
         }
     }
 
+    events_task.abort();
+    let grace = Duration::from_millis(app_state.read().await.config.shutdown_grace_ms);
+    // Whatever hasn't finished within the grace period is dropped with the runtime.
+    let _ = tokio::time::timeout(grace, futures::future::join_all(action_tasks)).await;
     terminal.clear()?;
     let mut stdout = io::stdout();
     execute!(stdout, LeaveAlternateScreen, DisableMouseCapture)?;
@@ -80,6 +275,30 @@ pub async fn start_ui(app_state: SharedState) -> Result<(), io::Error> {
     Ok(())
 }
 
+fn selected_id(app: &AppState) -> Option<String> {
+    app.selected_container()
+        .map(|container| container.id.clone())
+}
+
+/// Hands the terminal to `task` (e.g. an external pager) and restores the TUI afterwards.
+/// Failures of the task itself are ignored so a missing pager doesn't end the session.
+fn run_suspended<B, F>(terminal: &mut Terminal<B>, task: F) -> Result<(), io::Error>
+where
+    B: ratatui::backend::Backend,
+    F: FnOnce() -> io::Result<()>,
+{
+    execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
+    disable_raw_mode()?;
+    terminal.show_cursor()?;
+
+    let _ = task();
+
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+    terminal.clear()?;
+    Ok(())
+}
+
 fn draw_ui(f: &mut Frame, app_state: &AppState) {
     let area = f.area();
 
@@ -108,23 +327,342 @@ fn draw_ui(f: &mut Frame, app_state: &AppState) {
         AppMode::Help => {
             draw_help(f, area);
         }
+        AppMode::Resources if app_state.stats_only => {
+            draw_resource_panel(f, area, app_state);
+        }
         AppMode::Resources => {
             draw_normal_mode(f, area, app_state, true);
             draw_resource_graph(f, area, app_state);
         }
+        AppMode::Details => {
+            draw_normal_mode(f, area, app_state, true);
+            draw_details_mode(f, area, app_state);
+        }
+        AppMode::Labels => {
+            draw_normal_mode(f, area, app_state, true);
+            draw_labels_mode(f, area, app_state);
+        }
+        AppMode::Events => {
+            draw_normal_mode(f, area, app_state, true);
+            draw_events_mode(f, area, app_state);
+        }
+        AppMode::Diagnostics => {
+            draw_normal_mode(f, area, app_state, true);
+            draw_diagnostics_mode(f, area, app_state);
+        }
+        AppMode::Rename => {
+            draw_normal_mode(f, area, app_state, true);
+            let title = match &app_state.prompt_error {
+                Some(error) => format!("Rename - {error}"),
+                None => "Rename".to_string(),
+            };
+            draw_prompt(f, area, &title, "", &app_state.rename_input);
+        }
+        AppMode::QuickOpen => {
+            draw_normal_mode(f, area, app_state, false);
+            draw_prompt(
+                f,
+                area,
+                "Go to container",
+                "> ",
+                &app_state.quick_open_input,
+            );
+        }
+        AppMode::ConfirmRemove => {
+            draw_normal_mode(f, area, app_state, true);
+            let name = app_state
+                .selected_container()
+                .map(|container| container.names.trim_start_matches('/'))
+                .unwrap_or_default();
+            let title = match &app_state.prompt_error {
+                Some(error) => format!("Remove {name} - {error}"),
+                None => format!("Remove {name}? Type its name or \"yes\" to confirm"),
+            };
+            draw_prompt(f, area, &title, "", &app_state.confirm_input);
+        }
+        AppMode::ConfirmAction => {
+            draw_normal_mode(f, area, app_state, true);
+            let selected = app_state.selected_container();
+            let name = match (app_state.action_targets.len(), selected) {
+                (1, Some(container)) => container.names.trim_start_matches('/').to_string(),
+                (n, _) => {
+                    let project = selected.and_then(|container| container.compose_project());
+                    draw_action_targets(f, area, app_state);
+                    format!("project {} ({n} containers)", project.unwrap_or_default())
+                }
+            };
+            let action = app_state.confirm_action.map_or("", |action| action.label());
+            let title = match &app_state.prompt_error {
+                Some(error) => format!("{action} {name} - {error}"),
+                None => format!("{action} {name}? Seconds before SIGKILL, Enter to confirm"),
+            };
+            draw_prompt(f, area, &title, "", &app_state.timeout_input);
+        }
+    }
+}
+
+/// Lists the containers a batch action is about to hit, just above its prompt.
+fn draw_action_targets(f: &mut Frame, area: Rect, app_state: &AppState) {
+    let names: Vec<Line> = app_state
+        .action_targets
+        .iter()
+        .map(|id| {
+            let name = app_state
+                .container_data
+                .iter()
+                .find(|container| &container.id == id)
+                .map_or(id.as_str(), |container| {
+                    container.names.trim_start_matches('/')
+                });
+            Line::from(name.to_string())
+        })
+        .collect();
+    // Stays clear of the three-row prompt below it.
+    let height = (names.len() as u16 + 2).min(area.height.saturating_sub(3) / 2);
+    let list_area = Rect {
+        x: area.x,
+        y: area.y + area.height.saturating_sub(3 + height),
+        width: area.width,
+        height,
+    };
+    f.render_widget(Clear, list_area);
+    f.render_widget(
+        Paragraph::new(names).block(
+            Block::default()
+                .title("Affected containers")
+                .borders(Borders::ALL),
+        ),
+        list_area,
+    );
+}
+
+fn draw_details_mode(f: &mut Frame, area: Rect, app_state: &AppState) {
+    let Some(container) = app_state.selected_container() else {
+        return;
+    };
+    let fields = [
+        ("ID", container.id.as_str()),
+        ("Image", container.image.as_str()),
+        ("Names", container.names.as_str()),
+        ("Status", container.status.as_str()),
+        ("Command", container.command.as_str()),
+        ("IP", container.ip.as_str()),
+        (
+            "Restart policy",
+            container.restart_policy.as_deref().unwrap_or("N/A"),
+        ),
+        (
+            "Created",
+            &format_created(container, app_state).unwrap_or("N/A".to_string()),
+        ),
+    ];
+    let lines: Vec<Line> = fields
+        .iter()
+        .map(|(key, value)| {
+            Line::from(vec![
+                Span::styled(format!("{key}: "), Style::default().fg(Color::Yellow)),
+                Span::raw(value.to_string()),
+            ])
+        })
+        .collect();
+
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(format!("Details - {}", container.names))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app_state.config.borders.details)),
+        )
+        .wrap(Wrap { trim: false });
+
+    let overlay_area = overlay_rect(app_state.config.overlay, area);
+    f.render_widget(Clear, overlay_area);
+    f.render_widget(paragraph, overlay_area);
+}
+
+fn draw_events_mode(f: &mut Frame, area: Rect, app_state: &AppState) {
+    let overlay_area = overlay_rect(app_state.config.overlay, area);
+    let lines: Vec<Line> = if app_state.events.is_empty() {
+        vec![Line::from("No events yet")]
+    } else {
+        app_state
+            .events
+            .iter()
+            .map(|(at, description)| {
+                Line::from(format!(
+                    "{} {}",
+                    format_timestamp(
+                        *at,
+                        &app_state.config.time_format,
+                        app_state.config.time_zone,
+                    ),
+                    description
+                ))
+            })
+            .collect()
+    };
+    // Keep the newest events in view.
+    let scroll = lines
+        .len()
+        .saturating_sub(overlay_area.height.saturating_sub(2) as usize);
+
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title("Events")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app_state.config.borders.events)),
+        )
+        .scroll((scroll as u16, 0));
+
+    f.render_widget(Clear, overlay_area);
+    f.render_widget(paragraph, overlay_area);
+}
+
+fn draw_diagnostics_mode(f: &mut Frame, area: Rect, app_state: &AppState) {
+    let overlay_area = overlay_rect(app_state.config.overlay, area);
+    let diagnostics = &app_state.diagnostics;
+    let lines: Vec<Line> = if diagnostics.is_empty() {
+        vec![Line::from("Nothing to report")]
+    } else {
+        diagnostics
+            .entries()
+            .map(|diagnostic| {
+                let color = match diagnostic.level {
+                    Level::Error => Color::Red,
+                    Level::Warn => Color::Yellow,
+                    Level::Info => Color::Reset,
+                    Level::Debug => Color::DarkGray,
+                };
+                Line::from(vec![
+                    Span::raw(format!(
+                        "{} ",
+                        format_system_time(
+                            diagnostic.at,
+                            &app_state.config.time_format,
+                            app_state.config.time_zone,
+                        )
+                    )),
+                    Span::styled(
+                        format!("{:<5} ", diagnostic.level.label()),
+                        Style::default().fg(color),
+                    ),
+                    Span::raw(diagnostic.message.as_str()),
+                ])
+            })
+            .collect()
+    };
+    // Keep the newest diagnostics in view, like the events overlay.
+    let scroll = lines
+        .len()
+        .saturating_sub(overlay_area.height.saturating_sub(2) as usize);
+    let title = match diagnostics.verbosity {
+        Level::Debug => "Diagnostics - everything".to_string(),
+        level => format!(
+            "Diagnostics - {} and above, --verbose for more",
+            level.label().to_lowercase()
+        ),
+    };
+
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app_state.config.borders.diagnostics)),
+        )
+        .scroll((scroll as u16, 0));
+
+    f.render_widget(Clear, overlay_area);
+    f.render_widget(paragraph, overlay_area);
+}
+
+fn format_created(container: &ContainerInfo, app_state: &AppState) -> Option<String> {
+    container.created.map(|secs| {
+        format_timestamp(
+            secs,
+            &app_state.config.created_format,
+            app_state.config.time_zone,
+        )
+    })
+}
+
+fn draw_labels_mode(f: &mut Frame, area: Rect, app_state: &AppState) {
+    let Some(container) = app_state.selected_container() else {
+        return;
+    };
+    let rows: Vec<Row> = container
+        .labels
+        .iter()
+        .skip(app_state.vertical_scroll as usize)
+        .map(|(key, value)| {
+            Row::new(vec![
+                Cell::from(key.as_str()).style(Style::default().fg(Color::Yellow)),
+                Cell::from(value.as_str()),
+            ])
+        })
+        .collect();
+    let key_width = container
+        .labels
+        .keys()
+        .map(|key| key.chars().count())
+        .max()
+        .unwrap_or(0) as u16;
+
+    let block = Block::default()
+        .title(format!(
+            "Labels - {} ({})",
+            container.names,
+            container.labels.len()
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app_state.config.borders.labels));
+    let table = if rows.is_empty() {
+        Table::new(
+            vec![Row::new(vec![Cell::from("No labels")])],
+            [Constraint::Fill(1)],
+        )
+    } else {
+        Table::new(rows, [Constraint::Length(key_width), Constraint::Fill(1)])
     }
+    .block(block);
+
+    let overlay_area = overlay_rect(app_state.config.overlay, area);
+    f.render_widget(Clear, overlay_area);
+    f.render_widget(table, overlay_area);
+}
+
+/// Roughly one time label per this many columns of chart width.
+const COLUMNS_PER_TIME_LABEL: u16 = 16;
+
+fn time_axis_labels(x_start: f64, x_end: f64, width: u16) -> Vec<String> {
+    let count = (width / COLUMNS_PER_TIME_LABEL).max(2) as usize;
+    let step = (x_end - x_start) / (count - 1) as f64;
+    (0..count)
+        .map(|i| ((x_start + step * i as f64).round() as i64).to_string())
+        .collect()
 }
 
+/// `baseline` is drawn dimmed behind the live data, already aligned to its
+/// start; it may reach further, in which case the time axis does too.
 fn get_stats_graph<'a>(
-    data_points: &'a Vec<(f64, f64)>,
+    data_points: &'a [(f64, f64)],
+    baseline: &'a [(f64, f64)],
     max_value: f64,
     title: &'a str,
+    width: u16,
+    (color, marker): (Color, symbols::Marker),
 ) -> Chart<'a> {
     let dataset = Dataset::default()
-        .marker(symbols::Marker::Braille)
+        .marker(marker)
+        .graph_type(ratatui::widgets::GraphType::Line)
+        .style(Style::default().fg(color))
+        .data(data_points);
+    let baseline_dataset = Dataset::default()
+        .marker(marker)
         .graph_type(ratatui::widgets::GraphType::Line)
-        .style(Style::default().fg(Color::Cyan))
-        .data(&data_points);
+        .style(Style::default().fg(Color::DarkGray))
+        .data(baseline);
 
     let mut x_start = 0.0;
     let mut x_end = 1.0;
@@ -132,19 +670,20 @@ fn get_stats_graph<'a>(
         x_start = data_points[0].0;
         x_end = data_points[data_points.len() - 1].0;
     }
-    let rounded_start = x_start.round() as i64;
-    let rounded_end = x_end.round() as i64;
-
+    if let Some((last, _)) = baseline.last() {
+        x_end = x_end.max(*last);
+    }
     let y_end = max_value;
     let y_mid = y_end / 2.0;
 
-    Chart::new(vec![dataset])
+    // Later datasets draw on top, so the live one goes last.
+    Chart::new(vec![baseline_dataset, dataset])
         .block(Block::default().borders(Borders::NONE))
         .x_axis(
             Axis::default()
                 .title("Time (s)")
                 .bounds([x_start, x_end])
-                .labels(vec![rounded_start.to_string(), rounded_end.to_string()]),
+                .labels(time_axis_labels(x_start, x_end, width)),
         )
         .y_axis(
             Axis::default()
@@ -153,44 +692,219 @@ fn get_stats_graph<'a>(
                 .bounds([-1.0, y_end])
                 .labels(vec![
                     "0.0".to_string(),
-                    format!("{:.2}", y_mid).into(),
-                    format!("{:.2}", y_end).into(),
+                    format!("{:.2}", y_mid),
+                    format!("{:.2}", y_end),
                 ]),
         )
 }
 
 fn draw_resource_graph(f: &mut Frame, area: Rect, app_state: &AppState) {
-    let cpu_points: Vec<(f64, f64)> = app_state.cpu_data.data.iter().cloned().collect();
-    let cpu_max = app_state.cpu_data.get_max().unwrap_or(101.0);
-    let cpu_chart = get_stats_graph(&cpu_points, cpu_max, "CPU %");
-
-    let mem_points: Vec<(f64, f64)> = app_state.mem_data.data.iter().cloned().collect();
-    let mem_max = app_state.mem_data.get_max().unwrap_or(101.0);
-    let mem_chart = get_stats_graph(&mem_points, mem_max, "Memory %");
+    draw_resource_panel(f, overlay_rect(app_state.config.overlay, area), app_state);
+}
 
-    let overlay_area = centered_rect(80, 80, area);
+fn draw_resource_panel(f: &mut Frame, overlay_area: Rect, app_state: &AppState) {
+    // No background color, so themed and transparent terminals show through.
+    let title = match app_state.selected_container() {
+        Some(container) => format!("Resource Usage - {}", source_label(container)),
+        None => "Resource Usage".to_string(),
+    };
     let outer_block = Block::default()
-        .title("Resource Usage")
+        .title(title)
         .borders(Borders::ALL)
-        .style(Style::default().bg(Color::Black));
+        .border_style(Style::default().fg(app_state.config.borders.resources));
     f.render_widget(Clear, overlay_area);
     f.render_widget(outer_block, overlay_area);
 
+    if let Some(message) = &app_state.stats_message {
+        let paragraph = Paragraph::new(message.as_str())
+            .style(Style::default().fg(Color::Yellow))
+            .alignment(Alignment::Center);
+        f.render_widget(paragraph, centered_rect(90, 20, overlay_area));
+        return;
+    }
+
+    let metrics = match app_state.focused_metric {
+        Some(metric) => vec![metric],
+        None => app_state.config.stats.clone(),
+    };
+    if metrics.is_empty() {
+        let paragraph = Paragraph::new("No metrics enabled in the `stats` config")
+            .style(Style::default().fg(Color::Yellow))
+            .alignment(Alignment::Center);
+        f.render_widget(paragraph, centered_rect(90, 20, overlay_area));
+        return;
+    }
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .margin(1)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .horizontal_margin(2)
+        .vertical_margin(1)
+        .constraints(vec![
+            Constraint::Ratio(1, metrics.len() as u32);
+            metrics.len()
+        ])
         .split(overlay_area);
 
-    f.render_widget(cpu_chart, centered_rect(90, 90, chunks[0]));
-    f.render_widget(mem_chart, centered_rect(90, 90, chunks[1]));
+    // Samples stay aggregate; normalizing is only a matter of display.
+    let cpu_divisor = match (app_state.config.cpu_scale, app_state.online_cpus) {
+        (CpuScale::Normalized, Some(cores)) => cores.max(1) as f64,
+        _ => 1.0,
+    };
+    let cpu_points: Vec<(f64, f64)> = app_state
+        .cpu_data
+        .data
+        .iter()
+        .map(|(x, cpu)| (*x, cpu / cpu_divisor))
+        .collect();
+    let mem_points: Vec<(f64, f64)> = app_state.mem_data.data.iter().cloned().collect();
+    let baseline = app_state.shown_baseline();
+    let cpu_baseline = baseline
+        .map(|baseline| align_baseline(&baseline.cpu, &cpu_points, cpu_divisor))
+        .unwrap_or_default();
+    let mem_baseline = baseline
+        .map(|baseline| align_baseline(&baseline.mem, &mem_points, 1.0))
+        .unwrap_or_default();
+    let baseline_note = if baseline.is_some() {
+        " (baseline dimmed)"
+    } else {
+        ""
+    };
+    let limits = app_state.resource_limits.unwrap_or_default();
+    let latest = |data: &[(f64, f64)]| data.last().map(|(_, y)| *y);
+
+    for (metric, chunk) in metrics.into_iter().zip(chunks.iter()) {
+        let style = app_state.config.graph_palette.graph_style(metric);
+        let chart = match metric {
+            Metric::Cpu => {
+                let scale = app_state.config.cpu_scale;
+                let (axis_cores, axis_title) = match scale {
+                    CpuScale::Aggregate => (app_state.online_cpus, "CPU %"),
+                    CpuScale::Normalized => (Some(1), "CPU % (normalized)"),
+                };
+                let observed = highest(&cpu_points, &cpu_baseline);
+                let cpu_max = cpu_axis_max(observed, axis_cores);
+                let aggregate = app_state.cpu_data.data.back().map(|(_, cpu)| *cpu);
+                let context =
+                    cpu_limit_context(aggregate, limits.cpus, app_state.online_cpus, scale);
+                get_stats_graph(
+                    &cpu_points,
+                    &cpu_baseline,
+                    cpu_max,
+                    axis_title,
+                    chunk.width,
+                    style,
+                )
+                .block(Block::default().title(context + baseline_note))
+            }
+            Metric::Mem => {
+                let mem_max = highest(&mem_points, &mem_baseline).unwrap_or(100.0);
+                let context = memory_limit_context(latest(&mem_points), limits.memory);
+                get_stats_graph(
+                    &mem_points,
+                    &mem_baseline,
+                    mem_max,
+                    "Memory %",
+                    chunk.width,
+                    style,
+                )
+                .block(Block::default().title(context + baseline_note))
+            }
+        };
+        f.render_widget(chart, *chunk);
+    }
+}
+
+/// Shifts a baseline in time so it starts where the live data does, and
+/// scales it like the live data.
+fn align_baseline(
+    baseline: &MaxSlidingWindow<f64>,
+    live: &[(f64, f64)],
+    divisor: f64,
+) -> Vec<(f64, f64)> {
+    let Some((baseline_start, _)) = baseline.data.front() else {
+        return Vec::new();
+    };
+    let live_start = live.first().map_or(0.0, |(x, _)| *x);
+    baseline
+        .data
+        .iter()
+        .map(|(x, y)| (x - baseline_start + live_start, y / divisor))
+        .collect()
+}
+
+fn highest(live: &[(f64, f64)], baseline: &[(f64, f64)]) -> Option<f64> {
+    live.iter()
+        .chain(baseline)
+        .map(|(_, y)| *y)
+        .max_by(f64::total_cmp)
+}
+
+/// Top of the CPU axis: the highest sample rounded up to 1, 2 or 5 times a
+/// power of ten, so busy multi-core containers go past 100 without clipping.
+/// It stays within the `cores` × 100% a container can actually use.
+fn cpu_axis_max(observed: Option<f64>, cores: Option<u64>) -> f64 {
+    let Some(observed) = observed.filter(|max| *max > 0.0) else {
+        return 100.0;
+    };
+    let magnitude = 10f64.powf(observed.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|factor| factor * magnitude)
+        .find(|step| *step >= observed)
+        .unwrap_or(observed);
+    match cores {
+        Some(cores) => step.min((cores.max(1) * 100) as f64).max(observed),
+        None => step,
+    }
+}
+
+/// Aggregate CPU % is relative to one core, so it is divided by the core
+/// limit; without one, the title says how the scale relates to this host.
+fn cpu_limit_context(
+    usage: Option<f64>,
+    cpus: Option<f64>,
+    cores: Option<u64>,
+    scale: CpuScale,
+) -> String {
+    match (usage, cpus, cores) {
+        (Some(usage), Some(cpus), _) => {
+            format!("CPU: using {:.0}% of {}-core limit", usage / cpus, cpus)
+        }
+        (None, Some(cpus), _) => format!("CPU: {}-core limit", cpus),
+        (_, None, Some(cores)) => match scale {
+            CpuScale::Aggregate => format!("CPU: no limit, of {}% max", cores * 100),
+            CpuScale::Normalized => format!("CPU: no limit, normalized over {cores} cores"),
+        },
+        (_, None, None) => "CPU: no limit".to_string(),
+    }
+}
+
+/// Memory % is already relative to the limit when one is set.
+fn memory_limit_context(usage: Option<f64>, memory: Option<u64>) -> String {
+    match (usage, memory) {
+        (Some(usage), Some(memory)) => format!(
+            "Memory: using {:.0}% of {} limit",
+            usage,
+            format_bytes(memory)
+        ),
+        (None, Some(memory)) => format!("Memory: {} limit", format_bytes(memory)),
+        (_, None) => "Memory: no limit".to_string(),
+    }
+}
+
+fn describe_key(key: &KeyEvent) -> String {
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        format!("Ctrl+{}", key.code)
+    } else {
+        key.code.to_string()
+    }
 }
 
 fn draw_help(f: &mut Frame, area: Rect) {
     let lines: Vec<Line> = default_keybindings()
         .iter()
         .map(|binding| {
-            let keys: Vec<String> = binding.keys.iter().map(|key| format!("{}", key)).collect();
+            let keys: Vec<String> = binding.keys.iter().map(describe_key).collect();
             let key_text = keys.join(" / ");
 
             Line::from(vec![
@@ -216,79 +930,277 @@ fn draw_help(f: &mut Frame, area: Rect) {
 }
 
 fn draw_search_mode(f: &mut Frame, area: Rect, app_state: &AppState) {
-    let search_prompt = Paragraph::new(Span::raw(format!("/{}", app_state.search_query)))
-        .block(Block::default().borders(Borders::ALL).title("Search"));
+    let title = if app_state.fuzzy_search {
+        "Search (fuzzy)"
+    } else {
+        "Search (literal)"
+    };
+    draw_prompt(f, area, title, "/", &app_state.search_query);
+}
 
-    let search_height = 3;
+fn draw_prompt(f: &mut Frame, area: Rect, title: &str, prefix: &str, input: &TextInput) {
+    let prompt = Paragraph::new(Span::raw(format!("{}{}", prefix, input.value()))).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title.to_string()),
+    );
+
+    let prompt_height = 3;
     let bottom_area = Rect {
         x: area.x,
-        y: area.y + area.height.saturating_sub(search_height),
+        y: area.y + area.height.saturating_sub(prompt_height),
         width: area.width,
-        height: search_height,
+        height: prompt_height,
     };
 
     f.render_widget(Clear, bottom_area);
-    f.render_widget(search_prompt, bottom_area);
+    f.render_widget(prompt, bottom_area);
+
+    let cursor_offset = Span::raw(prefix).width() + Span::raw(input.before_cursor()).width();
+    f.set_cursor_position((bottom_area.x + 1 + cursor_offset as u16, bottom_area.y + 1));
 }
 
 fn draw_context_mode(f: &mut Frame, area: Rect, app_state: &AppState) {
-    let items: Vec<ListItem> = app_state
+    let built_in = app_state
         .menu_items
         .iter()
-        .map(|s| ListItem::new(s.to_string()))
+        .map(|item| (item.key, item.label));
+    let custom = app_state
+        .config
+        .actions
+        .iter()
+        .map(|action| (action.key, action.label.as_str()));
+    let items: Vec<ListItem> = built_in
+        .chain(custom)
+        .map(|(key, label)| {
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{}) ", key), Style::default().fg(Color::Yellow)),
+                Span::raw(label),
+            ]))
+        })
         .collect();
     let mut state = ListState::default();
     state.select(Some(app_state.menu_selected));
+    let mut block = Block::default()
+        .title("Actions")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app_state.config.borders.menu));
+    if let Some(usage) = menu_usage_line(app_state) {
+        block = block.title_bottom(usage);
+    }
     let menu = List::new(items)
-        .block(
-            Block::default()
-                .title("Actions")
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Cyan)),
-        )
+        .block(block)
         .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
         .highlight_symbol(">> ");
-    let area = centered_rect(30, 20, area);
+    let area = overlay_rect(app_state.config.menu, area);
     f.render_widget(Clear, area);
     f.render_stateful_widget(menu, area, &mut state);
 }
 
-fn draw_logs_mode(f: &mut Frame, area: Rect, app_state: &AppState) -> Rect {
-    let log_spans: Vec<Line> = app_state
-        .logs
+/// A glance at how busy the menu's container is, e.g. " CPU 12% MEM 40% ".
+fn menu_usage_line(app_state: &AppState) -> Option<Line<'static>> {
+    let container = app_state.selected_container()?;
+    if !container.is_running() {
+        return None;
+    }
+    let Some(usage) = app_state.menu_usage else {
+        return Some(Line::from(" sampling... ").style(Style::default().fg(Color::DarkGray)));
+    };
+    let percent = |value: Option<f64>| value.map_or("-".to_string(), |v| format!("{v:.0}%"));
+    Some(Line::from(format!(
+        " CPU {} MEM {} ",
+        percent(usage.cpu),
+        percent(usage.memory)
+    )))
+}
+
+/// Groups consecutive identical lines into `(line, count)` pairs.
+fn collapse_duplicates(logs: &[String]) -> Vec<(&str, usize)> {
+    let mut collapsed: Vec<(&str, usize)> = Vec::new();
+    for line in logs {
+        match collapsed.last_mut() {
+            Some((last, count)) if *last == line.as_str() => *count += 1,
+            _ => collapsed.push((line, 1)),
+        }
+    }
+    collapsed
+}
+
+/// Number of trailing entries that contain the last `fresh_lines` log lines.
+fn fresh_entries(entries: &[(&str, usize)], fresh_lines: usize) -> usize {
+    let mut remaining = fresh_lines;
+    entries
         .iter()
-        .map(|line| {
-            if let Some(query) =
-                (!app_state.search_query.is_empty()).then_some(&app_state.search_query)
+        .rev()
+        .take_while(|(_, count)| {
+            let fresh = remaining > 0;
+            remaining = remaining.saturating_sub(*count);
+            fresh
+        })
+        .count()
+}
+
+fn draw_logs_mode(f: &mut Frame, area: Rect, app_state: &AppState) -> Rect {
+    let overlay_area = overlay_rect(app_state.config.overlay, area);
+    if app_state.split_view {
+        let [logs_area, stats_area] =
+            Layout::horizontal([Constraint::Percentage(50); 2]).areas(overlay_area);
+        draw_logs_panel(f, logs_area, app_state);
+        draw_resource_panel(f, stats_area, app_state);
+    } else {
+        draw_logs_panel(f, overlay_area, app_state);
+    }
+    overlay_area
+}
+
+fn draw_logs_panel(f: &mut Frame, overlay_area: Rect, app_state: &AppState) {
+    let entries: Vec<(&str, usize)> = if app_state.collapse_duplicates {
+        collapse_duplicates(&app_state.logs)
+    } else {
+        app_state
+            .logs
+            .iter()
+            .map(|line| (line.as_str(), 1))
+            .collect()
+    };
+    // Underline the last line read on the previous visit; indices only line up uncollapsed.
+    let unread = app_state.unread_logs();
+    let last_read = app_state
+        .unread_from
+        .filter(|_| unread > 0 && !app_state.collapse_duplicates);
+    let first_fresh = entries.len() - fresh_entries(&entries, app_state.fresh_log_lines());
+    let selection = app_state.log_selection_range();
+    let text_width = overlay_area.width.saturating_sub(2) as usize;
+    let max_line_chars = match app_state.config.long_line_screens {
+        0 => None,
+        _ if app_state.expand_long_lines => None,
+        screens => Some(text_width.max(1) * screens),
+    };
+    let log_spans: Vec<Line> = entries
+        .into_iter()
+        .enumerate()
+        .map(|(i, (raw, count))| {
+            let mut base_style = if i >= first_fresh {
+                Style::default().fg(Color::Green)
+            } else {
+                Style::default()
+            };
+            if Some(i + 1) == last_read {
+                base_style = base_style.add_modifier(Modifier::UNDERLINED);
+            }
+            let plain = strip_str(raw);
+            let structured = app_state
+                .structured_logs
+                .then(|| format_structured(&plain))
+                .flatten();
+            let suffix = if count > 1 {
+                format!(" (x{})", count)
+            } else {
+                String::new()
+            };
+            let line = match &structured {
+                Some(text) => format!("{}{}", text, suffix),
+                None if count > 1 => format!("{}{}", plain.trim_end_matches('\n'), suffix),
+                None => plain,
+            };
+            let rendered = if let Some(query) =
+                (!app_state.search_query.is_empty()).then_some(app_state.search_query.value())
+                && let Some(indices) = search::match_indices(query, &line, app_state.fuzzy_search)
             {
-                if line.contains(query) {
-                    let highlighted = line.replace(query, &format!("[{}]", query));
-                    Line::from(Span::styled(
-                        highlighted,
-                        Style::default().fg(Color::Yellow),
-                    ))
-                } else {
-                    Line::from(Span::raw(line.clone()))
-                }
+                highlight_matches(&line, &indices)
+            } else if app_state.ansi_colors && structured.is_none() {
+                let mut colored = parse_ansi(raw.trim_end_matches('\n'));
+                colored.push_span(Span::raw(suffix));
+                colored.style(base_style)
             } else {
-                Line::from(Span::raw(line.clone()))
+                Line::from(Span::styled(line, base_style))
+            };
+            let mut line = match max_line_chars {
+                Some(max_chars) => cut_long_line(rendered, max_chars),
+                None => rendered,
+            };
+            if selection.as_ref().is_some_and(|range| range.contains(&i)) {
+                line.style = line.style.add_modifier(Modifier::REVERSED);
             }
+            line
         })
         .collect();
 
     let logs_len = log_spans.len();
-    let image_name = app_state.container_data[app_state.selected].1[1].clone();
+    let widest_line = log_spans.iter().map(Line::width).max().unwrap_or(0);
+    let title = match app_state.selected_container() {
+        Some(container) if !container.is_running() => {
+            format!("Logs - {} (container exited)", source_label(container))
+        }
+        Some(container) => format!("Logs - {}", source_label(container)),
+        None => "Logs".to_string(),
+    };
+    let title = match unread {
+        0 => title,
+        1 => format!("{title} - 1 new line"),
+        n => format!("{title} - {n} new lines"),
+    };
+    let title = if app_state.config.log_filter.is_empty() {
+        title
+    } else if !app_state.log_filter_enabled {
+        format!("{title} [filter off]")
+    } else if app_state.hidden_log_lines > 0 {
+        format!("{title} [{} hidden]", app_state.hidden_log_lines)
+    } else {
+        title
+    };
+    let title = if app_state.logs_truncated {
+        format!("{title} (earlier lines truncated)")
+    } else {
+        title
+    };
+    let throughput = &app_state.log_throughput;
+    let title = if throughput.lines > 0 {
+        format!(
+            "{title} [{:.0} lines/s, {} received]",
+            throughput.lines_per_sec(Instant::now()),
+            format_bytes(throughput.bytes as u64)
+        )
+    } else {
+        title
+    };
 
-    let overlay_area = centered_rect(80, 80, area);
+    let position = scroll_position(
+        app_state.vertical_scroll as usize,
+        overlay_area.height.saturating_sub(2) as usize,
+        logs_len,
+    );
 
-    let paragraph = Paragraph::new(log_spans)
-        .block(
-            Block::default()
-                .title(format!("Logs - {}", image_name))
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Cyan)),
-        )
-        .scroll((app_state.vertical_scroll, app_state.horizontal_scroll));
+    let mut block = Block::default()
+        .title(title)
+        .title(Line::from(position).right_aligned())
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app_state.config.borders.logs));
+    if let Some(error) = &app_state.log_error {
+        block = block.title_bottom(
+            Line::from(format!(" {error} "))
+                .style(Style::default().fg(Color::White).bg(Color::Red)),
+        );
+    } else if let Some(range) = &selection {
+        let count = range.end() - range.start() + 1;
+        block = block.title_bottom(
+            Line::from(format!(
+                " VISUAL: {count} line{}, y to copy, Esc to cancel ",
+                if count == 1 { "" } else { "s" }
+            ))
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+        );
+    } else if app_state.wrap_logs {
+        block = block.title_bottom(Line::from(" wrapped (w) ").right_aligned());
+    }
+    let mut paragraph = Paragraph::new(log_spans).block(block);
+    if app_state.wrap_logs {
+        paragraph = paragraph
+            .wrap(Wrap { trim: false })
+            .scroll((app_state.vertical_scroll, 0));
+    } else {
+        paragraph = paragraph.scroll((app_state.vertical_scroll, app_state.horizontal_scroll));
+    }
 
     let scrollbar = Scrollbar::new(ratatui::widgets::ScrollbarOrientation::VerticalRight);
     let mut scrollbar_state =
@@ -304,7 +1216,100 @@ fn draw_logs_mode(f: &mut Frame, area: Rect, app_state: &AppState) -> Rect {
         }),
         &mut scrollbar_state,
     );
-    overlay_area
+    // Shows how far right `l` has scrolled; the bottom border is the error's when one is shown.
+    if !app_state.wrap_logs && app_state.log_error.is_none() && widest_line > text_width {
+        let mut horizontal_state = ScrollbarState::new(widest_line - text_width)
+            .position(app_state.horizontal_scroll.into());
+        f.render_stateful_widget(
+            Scrollbar::new(ratatui::widgets::ScrollbarOrientation::HorizontalBottom),
+            overlay_area.inner(Margin {
+                vertical: 0,
+                horizontal: 1,
+            }),
+            &mut horizontal_state,
+        );
+    }
+}
+
+/// Keeps the first `max_chars` characters of `line` and notes how many were cut.
+fn cut_long_line(line: Line<'static>, max_chars: usize) -> Line<'static> {
+    let total: usize = line
+        .spans
+        .iter()
+        .map(|span| span.content.chars().count())
+        .sum();
+    if total <= max_chars {
+        return line;
+    }
+    let mut remaining = max_chars;
+    let mut spans = Vec::new();
+    for span in line.spans {
+        if remaining == 0 {
+            break;
+        }
+        let count = span.content.chars().count();
+        if count <= remaining {
+            remaining -= count;
+            spans.push(span);
+        } else {
+            let kept: String = span.content.chars().take(remaining).collect();
+            spans.push(Span::styled(kept, span.style));
+            remaining = 0;
+        }
+    }
+    spans.push(Span::styled(
+        format!("… [+{} chars]", total - max_chars),
+        Style::default().fg(Color::DarkGray),
+    ));
+    Line::from(spans).style(line.style)
+}
+
+/// A search hit: the line in yellow with the matched characters inverted.
+fn highlight_matches(line: &str, indices: &[usize]) -> Line<'static> {
+    let base = Style::default().fg(Color::Yellow);
+    let matched = base.add_modifier(Modifier::REVERSED | Modifier::BOLD);
+    let mut spans: Vec<Span> = Vec::new();
+    let mut current = String::new();
+    let mut current_matched = false;
+    for (i, c) in line.chars().enumerate() {
+        let is_match = indices.contains(&i);
+        if is_match != current_matched && !current.is_empty() {
+            let style = if current_matched { matched } else { base };
+            spans.push(Span::styled(std::mem::take(&mut current), style));
+        }
+        current_matched = is_match;
+        current.push(c);
+    }
+    let style = if current_matched { matched } else { base };
+    spans.push(Span::styled(current, style));
+    Line::from(spans)
+}
+
+/// Names the container an overlay shows; images are often shared, names aren't.
+fn source_label(container: &ContainerInfo) -> String {
+    format!(
+        "{} ({})",
+        container.names.trim_start_matches('/'),
+        container.image
+    )
+}
+
+/// Describes the last visible log line, e.g. "line 340/1000 (34%)".
+fn scroll_position(scroll: usize, visible_height: usize, total: usize) -> String {
+    if total == 0 {
+        return "line 0/0".to_string();
+    }
+    let last_visible = (scroll + visible_height.max(1)).min(total);
+    format!(
+        "line {}/{} ({}%)",
+        last_visible,
+        total,
+        last_visible * 100 / total
+    )
+}
+
+fn overlay_rect(size: OverlaySize, area: Rect) -> Rect {
+    centered_rect(size.width, size.height, area)
 }
 
 fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
@@ -326,28 +1331,212 @@ fn get_constraints(percent: u16) -> Vec<Constraint> {
     ]
 }
 
-fn draw_normal_mode(f: &mut Frame, area: Rect, app_state: &AppState, blurred: bool) -> Rect {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .margin(1)
-        .constraints([Constraint::Percentage(100)].as_ref())
-        .split(area);
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1}{}", value, UNITS[unit])
+}
 
-    let rows: Vec<Row> = app_state
-        .container_data
-        .iter()
-        .enumerate()
-        .map(|(i, item)| {
-            let mut matched = false;
+const STALE_AFTER: Duration = Duration::from_secs(30);
+const VERY_STALE_AFTER: Duration = Duration::from_secs(120);
 
-            if let Some(query) =
-                (!app_state.search_query.is_empty()).then_some(&app_state.search_query)
-            {
-                if item.1[1].contains(query) {
-                    matched = true;
-                }
+/// `42s`, `3m` or `2h`, rounded down.
+fn format_age(age: Duration) -> String {
+    match age.as_secs() {
+        secs @ 0..60 => format!("{secs}s"),
+        secs @ 60..3600 => format!("{}m", secs / 60),
+        secs => format!("{}h", secs / 3600),
+    }
+}
+
+/// Gray while the list is fresh, then yellow and red as it goes stale.
+fn age_color(age: Duration) -> Color {
+    if age >= VERY_STALE_AFTER {
+        Color::Red
+    } else if age >= STALE_AFTER {
+        Color::Yellow
+    } else {
+        Color::DarkGray
+    }
+}
+
+/// Container counts plus, once usage has been sampled, the summed CPU and memory.
+fn summary_line(app_state: &AppState) -> Line<'static> {
+    let containers = &app_state.container_data;
+    let running = containers.iter().filter(|c| c.is_running()).count();
+    let stopped = containers
+        .iter()
+        .filter(|c| matches!(c.state.as_str(), "exited" | "dead" | "created"))
+        .count();
+    let mut spans = vec![
+        Span::raw(format!(" {} containers  ", containers.len())),
+        Span::styled(
+            format!("{running} running"),
+            Style::default().fg(Color::Green),
+        ),
+        Span::raw("  "),
+        Span::styled(
+            format!("{stopped} stopped"),
+            Style::default().fg(Color::Red),
+        ),
+    ];
+    let cpu: Vec<f64> = app_state
+        .cpu_history
+        .values()
+        .filter_map(|history| history.data.back().map(|(_, usage)| *usage))
+        .collect();
+    if !cpu.is_empty() {
+        spans.push(Span::raw(format!("  CPU {:.1}%", cpu.iter().sum::<f64>())));
+    }
+    let memory: Vec<u64> = containers
+        .iter()
+        .filter_map(|c| c.memory.map(|memory| memory.used))
+        .collect();
+    if !memory.is_empty() {
+        spans.push(Span::raw(format!(
+            "  Mem {}",
+            format_bytes(memory.iter().sum())
+        )));
+    }
+    Line::from(spans)
+}
+
+/// Gauges beside the table rows, so `visible` must be the table's own rows.
+fn draw_memory_panel(
+    f: &mut Frame,
+    area: Rect,
+    app_state: &AppState,
+    visible: &[usize],
+    style: Style,
+) {
+    f.render_widget(
+        Block::default()
+            .title("Memory")
+            .borders(Borders::ALL)
+            .style(style),
+        area,
+    );
+
+    // Rows start below the table's top border and header line.
+    let first_row = area.y + 2;
+    let last_row = area.bottom().saturating_sub(1);
+    for (row, &i) in visible.iter().enumerate() {
+        let container = &app_state.container_data[i];
+        let y = first_row + row as u16;
+        if y >= last_row {
+            break;
+        }
+        let row_area = Rect {
+            x: area.x + 1,
+            y,
+            width: area.width.saturating_sub(2),
+            height: 1,
+        };
+        match container.memory {
+            Some(memory) => {
+                let gauge = memory_gauge(memory, app_state.config.graph_palette);
+                f.render_widget(gauge.style(style), row_area);
             }
+            None => f.render_widget(Paragraph::new("-").style(style), row_area),
+        }
+    }
+}
+
+fn memory_gauge(memory: MemoryUsage, palette: GraphPalette) -> LineGauge<'static> {
+    let ratio = memory.ratio();
+    let color = palette.level_color(ratio);
+    LineGauge::default()
+        .filled_style(Style::default().fg(color))
+        .unfilled_style(Style::default().fg(Color::DarkGray))
+        .label(format!(
+            "{}/{}",
+            format_bytes(memory.used),
+            format_bytes(memory.limit)
+        ))
+        .ratio(ratio)
+}
+
+/// Five log lines plus borders.
+const PREVIEW_HEIGHT: u16 = 7;
+
+fn draw_preview_panel(f: &mut Frame, area: Rect, app_state: &AppState, style: Style) {
+    let lines: Vec<Line> = if app_state.preview_logs.is_empty() {
+        vec![Line::from("No recent logs")]
+    } else {
+        app_state
+            .preview_logs
+            .iter()
+            .map(|line| Line::from(line.as_str()))
+            .collect()
+    };
+    let title = match app_state.selected_container() {
+        Some(container) => format!("Recent logs - {}", container.names),
+        None => "Recent logs".to_string(),
+    };
+    let paragraph = Paragraph::new(lines)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .style(style);
+    f.render_widget(paragraph, area);
+}
+
+fn draw_normal_mode(f: &mut Frame, area: Rect, app_state: &AppState, blurred: bool) -> Rect {
+    let mut chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([Constraint::Percentage(100)].as_ref())
+        .split(area);
+
+    let mut panel_style = Style::default();
+    if blurred {
+        panel_style = panel_style.add_modifier(Modifier::DIM);
+    }
+    if app_state.show_summary {
+        chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(chunks[0]);
+        f.render_widget(
+            Paragraph::new(summary_line(app_state)).style(panel_style),
+            chunks[0],
+        );
+        chunks = chunks[1..].into();
+    }
+    if app_state.show_preview {
+        chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(PREVIEW_HEIGHT)])
+            .split(chunks[0]);
+        draw_preview_panel(f, chunks[1], app_state, panel_style);
+    }
+    let visible = app_state.visible_containers();
+    if app_state.show_memory {
+        chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Length(32)])
+            .split(chunks[0]);
+        draw_memory_panel(f, chunks[1], app_state, &visible, panel_style);
+    }
+
+    let mut header = vec!["ID", "Image", "Status", "Names", "Command", "IP"];
+    // The creation time explains the order when sorting by it.
+    let show_created = app_state.config.sort != ContainerSort::Name;
+    if show_created {
+        header.push("Created");
+    }
+    if app_state.show_cpu {
+        header.push("CPU");
+    }
+    let widths = column_widths(header.len(), Block::bordered().inner(chunks[0]));
 
+    let rows: Vec<Row> = visible
+        .iter()
+        .map(|&i| {
+            let item = &app_state.container_data[i];
             let mut style = if i == app_state.selected {
                 Style::default().add_modifier(Modifier::REVERSED)
             } else {
@@ -356,21 +1545,47 @@ fn draw_normal_mode(f: &mut Frame, area: Rect, app_state: &AppState, blurred: bo
             if blurred {
                 style = style.add_modifier(Modifier::DIM);
             }
-            if matched {
-                style = style.bg(Color::Cyan);
+            let mut cells: Vec<Cell> = item
+                .row()
+                .into_iter()
+                .enumerate()
+                .map(|(column, mut value)| {
+                    if column == NAMES_COLUMN && app_state.watched.contains(&item.id) {
+                        value = format!("{WATCH_MARKER}{value}");
+                    }
+                    if column == NAMES_COLUMN && app_state.pinned.contains(&item.id) {
+                        value = format!("{PIN_MARKER}{value}");
+                    }
+                    let cell = Cell::from(truncate(&value, widths[column] as usize));
+                    let color = match column {
+                        STATUS_COLUMN => status_color(item),
+                        IMAGE_COLUMN if item.image_missing => Some(Color::Red),
+                        IP_COLUMN => ip_color(item),
+                        _ => None,
+                    };
+                    match color {
+                        Some(color) => cell.style(Style::default().fg(color)),
+                        None => cell,
+                    }
+                })
+                .collect();
+            if show_created {
+                let created = format_created(item, app_state).unwrap_or_default();
+                let width = widths[cells.len()] as usize;
+                cells.push(Cell::from(truncate(&created, width)));
+            }
+            if app_state.show_cpu {
+                let line = app_state
+                    .cpu_history
+                    .get(&item.id)
+                    .map(|history| sparkline(history, SPARKLINE_WIDTH))
+                    .unwrap_or_default();
+                cells.push(Cell::from(line).style(Style::default().fg(Color::Green)));
             }
-            Row::new(
-                item.1
-                    .iter()
-                    .map(|s| Cell::from(s.clone()))
-                    .collect::<Vec<_>>(),
-            )
-            .style(style)
+            Row::new(cells).style(style)
         })
         .collect();
 
-    let widths = [Constraint::Min(10); 5];
-
     let mut header_style = Style::default().add_modifier(Modifier::BOLD);
     let mut title_style = Style::default();
     if blurred {
@@ -378,63 +1593,164 @@ fn draw_normal_mode(f: &mut Frame, area: Rect, app_state: &AppState, blurred: bo
         title_style = title_style.add_modifier(Modifier::DIM);
     }
 
-    let table = Table::new(rows, widths)
+    let mut title = match &app_state.label_filter {
+        Some(label) => format!("Docker Containers [label: {}]", label),
+        None => "Docker Containers".to_string(),
+    };
+    if let Some(filter) = app_state.container_filter() {
+        title.push_str(&format!(" [filter: {filter}]"));
+    }
+    match app_state.config.sort {
+        ContainerSort::Name => {}
+        ContainerSort::Newest => title.push_str(" [newest first]"),
+        ContainerSort::Oldest => title.push_str(" [oldest first]"),
+    }
+    if app_state.paused {
+        title.push_str(" [PAUSED]");
+    }
+
+    let mut block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .style(title_style);
+    if let Some(error) = &app_state.refresh_error {
+        block = block.title_top(
+            Line::from(format!(" Docker error: {error} "))
+                .style(Style::default().fg(Color::White).bg(Color::Red))
+                .right_aligned(),
+        );
+    }
+    if let Some(last_refresh) = app_state.last_refresh {
+        let age = last_refresh.elapsed();
+        block = block.title_bottom(
+            Line::from(format!(" updated {} ago ", format_age(age)))
+                .style(Style::default().fg(age_color(age))),
+        );
+    }
+    if let Some(notification) = &app_state.notification {
+        block = block.title_bottom(Line::from(notification.as_str()).right_aligned());
+    }
+
+    let table = Table::new(rows, widths.into_iter().map(Constraint::Length))
         .header(
-            Row::new(vec![
-                Cell::from("ID"),
-                Cell::from("Image"),
-                Cell::from("Status"),
-                Cell::from("Names"),
-                Cell::from("IP"),
-            ])
-            .style(header_style),
+            Row::new(header.into_iter().map(Cell::from).collect::<Vec<_>>()).style(header_style),
         )
-        .block(
-            Block::default()
-                .title("Docker Containers")
-                .borders(Borders::ALL)
-                .style(title_style),
-        );
+        .block(block);
 
     f.render_widget(table, chunks[0]);
     area
 }
 
+/// Splits the table's inner width the way `Table` would lay out equal `Min(10)`
+/// columns, so cells can be cut to fit before rendering instead of clipped.
+fn column_widths(columns: usize, inner: Rect) -> Vec<u16> {
+    Layout::horizontal(vec![Constraint::Min(10); columns])
+        .flex(Flex::Start)
+        .spacing(1)
+        .split(inner)
+        .iter()
+        .map(|column| column.width)
+        .collect()
+}
+
+const IMAGE_COLUMN: usize = 1;
+const STATUS_COLUMN: usize = 2;
+const NAMES_COLUMN: usize = 3;
+/// Prefixes the names of containers watched for their exit.
+const WATCH_MARKER: &str = "◉ ";
+/// Prefixes the names of containers pinned to the top.
+const PIN_MARKER: &str = "▲ ";
+const IP_COLUMN: usize = 5;
+const SPARKLINE_WIDTH: usize = 10;
+const SPARKLINE_BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Renders the most recent `width` samples as bars scaled to 100% or the window's peak.
+fn sparkline(history: &MaxSlidingWindow<f64>, width: usize) -> String {
+    let max = history.get_max().unwrap_or(0.0).max(100.0);
+    let skip = history.data.len().saturating_sub(width);
+    history
+        .data
+        .iter()
+        .skip(skip)
+        .map(|(_, value)| {
+            let level = (value.max(0.0) / max * (SPARKLINE_BARS.len() - 1) as f64).round();
+            SPARKLINE_BARS[level as usize]
+        })
+        .collect()
+}
+
+/// Green for running/healthy, red for exited/unhealthy and yellow for transitional states.
+fn status_color(container: &ContainerInfo) -> Option<Color> {
+    if container.status.contains("(unhealthy)") {
+        return Some(Color::Red);
+    }
+    match container.state.as_str() {
+        "running" => Some(Color::Green),
+        "exited" | "dead" => Some(Color::Red),
+        "restarting" | "paused" | "created" | "removing" => Some(Color::Yellow),
+        _ => None,
+    }
+}
+
+/// The clipboard lives for the whole session: on X11 copied text is only
+/// available while its owner is alive.
+fn copy_to_clipboard(clipboard: &mut Option<Clipboard>, text: &str) -> Result<(), arboard::Error> {
+    if let Some(clipboard) = clipboard {
+        return clipboard.set_text(text);
+    }
+    clipboard.insert(Clipboard::new()?).set_text(text)
+}
+
+/// Dims expected gaps; a running container without an IP usually means a
+/// network misconfiguration, so it gets a warning color.
+fn ip_color(container: &ContainerInfo) -> Option<Color> {
+    if container.inspect_failed {
+        return Some(Color::DarkGray);
+    }
+    match (container.ip == NO_IP, container.is_running()) {
+        (false, _) => None,
+        (true, true) => Some(Color::Yellow),
+        (true, false) => Some(Color::DarkGray),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::vec;
 
     use super::*;
+    use crate::config::{CustomAction, TimeZone};
+    use crate::docker::{COMPOSE_PROJECT_LABEL, ContainerAction, MemoryUsage, UsageSnapshot};
     use ratatui::Terminal;
     use ratatui::backend::TestBackend;
 
     fn create_app_state_for_test(app_mode: &AppMode) -> AppState {
         AppState {
             container_data: vec![
-                (
-                    "id1".to_string(),
-                    vec![
-                        "id1".into(),
-                        "img1".into(),
-                        "running".into(),
-                        "name1".into(),
-                        "127.0.0.1".into(),
-                    ],
-                ),
-                (
-                    "id2".to_string(),
-                    vec![
-                        "id2".into(),
-                        "img2".into(),
-                        "exited".into(),
-                        "name2".into(),
-                        "127.0.0.2".into(),
-                    ],
-                ),
+                ContainerInfo {
+                    id: "id1".into(),
+                    image: "img1".into(),
+                    state: "running".into(),
+                    status: "running".into(),
+                    names: "name1".into(),
+                    command: "sleep infinity".into(),
+                    ip: "127.0.0.1".into(),
+                    restart_policy: Some("always".into()),
+                    ..Default::default()
+                },
+                ContainerInfo {
+                    id: "id2".into(),
+                    image: "img2".into(),
+                    state: "exited".into(),
+                    status: "exited".into(),
+                    names: "name2".into(),
+                    ip: "127.0.0.2".into(),
+                    ..Default::default()
+                },
             ],
             logs: std::iter::repeat_n("log_line".to_string(), 50).collect(),
             vertical_scroll: 10,
-            search_query: "log".to_string(),
+            search_query: "log".into(),
             mode: *app_mode,
             ..Default::default()
         }
@@ -451,9 +1767,12 @@ mod tests {
     }
 
     #[test]
-    fn test_draw_ui_context_mode_snapshot() {
+    fn test_draw_ui_watched_and_pinned_snapshot() {
         let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
-        let app = create_app_state_for_test(&AppMode::ContextMenu);
+        let mut app = create_app_state_for_test(&AppMode::Normal);
+        app.watched.insert("id1".into());
+        app.pinned.insert("id2".into());
+        app.notification = Some("Watching name1, you'll be told when it exits".into());
 
         terminal.draw(|f| draw_ui(f, &app)).unwrap();
 
@@ -461,12 +1780,132 @@ mod tests {
     }
 
     #[test]
-    fn test_draw_ui_stats_mode_snapshot() {
+    fn test_draw_ui_no_containers_snapshot() {
         let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
-        let mut app = create_app_state_for_test(&AppMode::Resources);
+        let mut app = create_app_state_for_test(&AppMode::Normal);
+        app.container_data.clear();
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_summary_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Normal);
+        app.show_summary = true;
+        app.container_data[0].memory = Some(MemoryUsage {
+            used: 256 * 1024 * 1024,
+            limit: 1024 * 1024 * 1024,
+        });
+        let mut history = MaxSlidingWindow::default();
+        history.add((0.0, 12.5));
+        app.cpu_history.insert("id1".into(), history);
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_overflowing_columns_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Normal);
+        app.container_data[0].names = "payments-reconciliation-worker-eu-west-1".into();
+        app.container_data[0].image =
+            "registry.example.com/platform/payments/reconciliation:2024.11.3".into();
+        app.container_data[0].status = "Up 3 weeks (healthy)".into();
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_log_search_multiple_matches_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Logs);
+        app.logs = vec![
+            "GET /health 200".into(),
+            "error: retrying after error".into(),
+            "error error error".into(),
+        ];
+        app.vertical_scroll = 0;
+        app.search_query = "error".into();
+        app.fuzzy_search = false;
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+        let buffer = terminal.backend().buffer();
+        let highlighted = |y: u16| -> String {
+            // Only inside the overlay; the table's selected row is reversed too.
+            (8..70)
+                .map(|x| &buffer[(x, y)])
+                .filter(|cell| cell.modifier.contains(Modifier::REVERSED))
+                .map(|cell| cell.symbol())
+                .collect()
+        };
+        assert_eq!("", highlighted(3));
+        assert_eq!("errorerror", highlighted(4));
+        assert_eq!("errorerrorerror", highlighted(5));
+    }
+
+    #[test]
+    fn test_draw_ui_log_throughput_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Logs);
+        app.log_throughput
+            .record_backlog(50, 2048, std::time::Instant::now());
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_log_filter_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Logs);
+        app.config.log_filter.exclude = vec!["GET /health".to_string()];
+        app.hidden_log_lines = 12;
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_log_truncated_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Logs);
+        app.logs_truncated = true;
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_log_selection_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Logs);
+        app.vertical_scroll = 0;
+        app.log_selection = Some((2, 1));
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_split_view_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(100, 24)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Logs);
+        app.split_view = true;
         app.cpu_data.add((1.0, 10.0));
         app.cpu_data.add((10.0, 40.0));
-
         app.mem_data.add((1.0, 5.0));
         app.mem_data.add((10.0, 32.0));
 
@@ -476,9 +1915,12 @@ mod tests {
     }
 
     #[test]
-    fn test_draw_ui_help_mode_snapshot() {
+    fn test_draw_ui_log_horizontal_scroll_snapshot() {
         let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
-        let app = create_app_state_for_test(&AppMode::Help);
+        let mut app = create_app_state_for_test(&AppMode::Logs);
+        app.logs = vec![format!("start {} end", "x".repeat(100)), "short".into()];
+        app.vertical_scroll = 0;
+        app.horizontal_scroll = 50;
 
         terminal.draw(|f| draw_ui(f, &app)).unwrap();
 
@@ -486,9 +1928,13 @@ mod tests {
     }
 
     #[test]
-    fn test_draw_ui_log_mode_snapshot() {
+    fn test_draw_ui_log_long_line_snapshot() {
         let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
-        let app = create_app_state_for_test(&AppMode::Logs);
+        let mut app = create_app_state_for_test(&AppMode::Logs);
+        app.logs = vec![format!("blob {}", "x".repeat(2000)), "short".into()];
+        app.vertical_scroll = 0;
+        app.wrap_logs = true;
+        app.config.long_line_screens = 2;
 
         terminal.draw(|f| draw_ui(f, &app)).unwrap();
 
@@ -496,10 +1942,22 @@ mod tests {
     }
 
     #[test]
-    fn test_draw_ui_search_logs_mode_snapshot() {
+    fn cuts_long_lines_across_spans() {
+        let line = Line::from(vec![Span::raw("abc"), Span::styled("defgh", Color::Red)]);
+        let cut = cut_long_line(line.clone(), 5);
+        let text: Vec<&str> = cut.spans.iter().map(|span| span.content.as_ref()).collect();
+        assert_eq!(vec!["abc", "de", "… [+3 chars]"], text);
+        assert_eq!(Some(Color::Red), cut.spans[1].style.fg);
+        assert_eq!(line, cut_long_line(line.clone(), 8));
+    }
+
+    #[test]
+    fn test_draw_ui_log_wrapped_snapshot() {
         let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
-        let mut app = create_app_state_for_test(&AppMode::Search);
-        app.last_mode = AppMode::Logs;
+        let mut app = create_app_state_for_test(&AppMode::Logs);
+        app.logs = vec![format!("start {} end", "x".repeat(100)), "short".into()];
+        app.vertical_scroll = 0;
+        app.wrap_logs = true;
 
         terminal.draw(|f| draw_ui(f, &app)).unwrap();
 
@@ -507,17 +1965,817 @@ mod tests {
     }
 
     #[test]
-    fn test_draw_ui_search_normal_mode_snapshot() {
+    fn test_draw_ui_log_error_snapshot() {
         let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
-        let mut app = create_app_state_for_test(&AppMode::Search);
-        app.last_mode = AppMode::Normal;
-        app.search_query = "img2".to_string();
+        let mut app = create_app_state_for_test(&AppMode::Logs);
+        app.log_error = Some("Error streaming logs: permission denied".to_string());
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_quick_open_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::QuickOpen);
+        app.quick_open_input = "name2".into();
+        app.selected = 1;
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_refresh_error_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Normal);
+        app.refresh_error = Some("permission denied".to_string());
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_last_refresh_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Normal);
+        app.last_refresh = Instant::now().checked_sub(Duration::from_secs(45));
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+        assert_eq!(Color::Yellow, terminal.backend().buffer()[(3, 18)].fg);
+    }
+
+    #[test]
+    fn formats_refresh_age() {
+        assert_eq!("0s", format_age(Duration::from_millis(900)));
+        assert_eq!("59s", format_age(Duration::from_secs(59)));
+        assert_eq!("2m", format_age(Duration::from_secs(150)));
+        assert_eq!("1h", format_age(Duration::from_secs(3700)));
+        assert_eq!(Color::DarkGray, age_color(Duration::from_secs(3)));
+        assert_eq!(Color::Red, age_color(VERY_STALE_AFTER));
+    }
+
+    #[test]
+    fn test_draw_ui_label_filter_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Normal);
+        app.label_filter = Some("app=web".to_string());
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_context_mode_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let app = create_app_state_for_test(&AppMode::ContextMenu);
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_context_mode_usage_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::ContextMenu);
+        app.menu_usage = Some(UsageSnapshot {
+            cpu: Some(12.3),
+            memory: Some(40.0),
+        });
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_context_mode_custom_action_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::ContextMenu);
+        app.config.actions = vec![CustomAction {
+            label: "Diagnose".into(),
+            key: 'g',
+            command: "docker exec {id} diagnose.sh".into(),
+            capture: false,
+        }];
+        app.menu_selected = app.menu_len() - 1;
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_stats_only_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Resources);
+        app.stats_only = true;
+        app.cpu_data.add((1.0, 10.0));
+        app.cpu_data.add((10.0, 40.0));
+        app.mem_data.add((1.0, 5.0));
+        app.mem_data.add((10.0, 32.0));
 
         terminal.draw(|f| draw_ui(f, &app)).unwrap();
 
         insta::assert_snapshot!(terminal.backend());
     }
 
+    #[test]
+    fn test_draw_ui_stats_mode_baseline_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Resources);
+        app.config.stats = vec![Metric::Cpu];
+        for (x, cpu) in [(1.0, 60.0), (5.0, 80.0), (10.0, 70.0)] {
+            app.cpu_data.add((x, cpu));
+        }
+        app.toggle_stats_baseline();
+        app.cpu_data.clear();
+        app.cpu_data.add((20.0, 10.0));
+        app.cpu_data.add((25.0, 20.0));
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn baseline_starts_with_the_live_data() {
+        let mut baseline = MaxSlidingWindow::default();
+        baseline.add((3.0, 40.0));
+        baseline.add((4.0, 80.0));
+        assert_eq!(
+            vec![(10.0, 20.0), (11.0, 40.0)],
+            align_baseline(&baseline, &[(10.0, 5.0)], 2.0)
+        );
+        assert_eq!(
+            vec![(0.0, 40.0), (1.0, 80.0)],
+            align_baseline(&baseline, &[], 1.0)
+        );
+    }
+
+    #[test]
+    fn test_draw_ui_stats_mode_normalized_cpu_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Resources);
+        app.config.stats = vec![Metric::Cpu];
+        app.config.cpu_scale = CpuScale::Normalized;
+        app.online_cpus = Some(4);
+        app.cpu_data.add((1.0, 40.0));
+        app.cpu_data.add((10.0, 300.0));
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_stats_mode_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Resources);
+        app.cpu_data.add((1.0, 10.0));
+        app.cpu_data.add((10.0, 40.0));
+
+        app.mem_data.add((1.0, 5.0));
+        app.mem_data.add((10.0, 32.0));
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_stats_mode_colorblind_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Resources);
+        app.config.graph_palette = GraphPalette::Colorblind;
+        app.cpu_data.add((1.0, 10.0));
+        app.cpu_data.add((10.0, 40.0));
+        app.mem_data.add((1.0, 5.0));
+        app.mem_data.add((10.0, 32.0));
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let colors: Vec<Color> = buffer.content().iter().map(|cell| cell.fg).collect();
+        assert!(!colors.contains(&Color::Cyan));
+        for metric in [Metric::Cpu, Metric::Mem] {
+            let (color, _) = GraphPalette::Colorblind.graph_style(metric);
+            assert!(colors.contains(&color));
+        }
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_stats_mode_full_window_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Resources);
+        for second in 0..70 {
+            let t = second as f64;
+            app.cpu_data.add((t, 50.0 + 40.0 * (t / 5.0).sin()));
+            app.mem_data.add((t, 20.0 + t / 2.0));
+        }
+        assert_eq!(60, app.cpu_data.data.len());
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_stats_mode_focused_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Resources);
+        app.focused_metric = Some(Metric::Mem);
+        app.mem_data.add((1.0, 5.0));
+        app.mem_data.add((10.0, 32.0));
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn resource_overlay_keeps_terminal_background() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let app = create_app_state_for_test(&AppMode::Resources);
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        assert!(buffer.content.iter().all(|cell| cell.bg == Color::Reset));
+    }
+
+    #[test]
+    fn test_draw_ui_stats_mode_no_stats_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Resources);
+        app.stats_message = Some("No stats available (container not running)".to_string());
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_log_preview_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Normal);
+        app.show_preview = true;
+        app.preview_logs = vec!["booting".to_string(), "ready on :8080".to_string()];
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_paused_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Normal);
+        app.paused = true;
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_events_mode_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Events);
+        app.config.time_zone = TimeZone::Utc;
+        for i in 0..20 {
+            app.push_event(12 * 3600 + i, "web restarted".to_string());
+        }
+        app.push_event(12 * 3600 + 20, "db died (137)".to_string());
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_diagnostics_mode_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let app = create_app_state_for_test(&AppMode::Diagnostics);
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_diagnostics_show_level_and_message() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Diagnostics);
+        app.diagnostics
+            .warn("Stats stream of id1: connection reset");
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let row: String = (0..80).map(|x| buffer[(x, 3)].symbol()).collect();
+        assert!(row.contains("WARN  Stats stream of id1: connection reset"));
+    }
+
+    #[test]
+    fn test_draw_ui_labels_mode_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Labels);
+        app.container_data[0].labels = [
+            ("com.docker.compose.project", "shop"),
+            ("com.docker.compose.service", "web"),
+            ("maintainer", "ops"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        app.vertical_scroll = 1;
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_details_mode_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let app = create_app_state_for_test(&AppMode::Details);
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_confirm_remove_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::ConfirmRemove);
+        app.confirm_input = "nam".into();
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_confirm_action_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::ConfirmAction);
+        app.confirm_action = Some(ContainerAction::Restart);
+        app.action_targets = vec!["id1".into()];
+        app.timeout_input = "10".into();
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_confirm_project_restart_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::ConfirmAction);
+        for container in &mut app.container_data {
+            container
+                .labels
+                .insert(COMPOSE_PROJECT_LABEL.into(), "shop".into());
+        }
+        app.confirm_action = Some(ContainerAction::Restart);
+        app.action_targets = vec!["id1".into(), "id2".into()];
+        app.timeout_input = "10".into();
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_rename_mode_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Rename);
+        app.rename_input = "new-name".into();
+        app.prompt_error = Some("Invalid container name: \"new name\"".to_string());
+        // A background task finishing mustn't replace the prompt's message.
+        app.notification = Some("Exported logs to tugboat-web.log".to_string());
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_log_mode_full_overlay_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Logs);
+        app.config.overlay = OverlaySize {
+            width: 100,
+            height: 100,
+        };
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_stats_mode_wide_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(200, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Resources);
+        for i in 0..60 {
+            app.cpu_data.add((i as f64, (i % 10) as f64 * 10.0));
+            app.mem_data.add((i as f64, 20.0 + (i % 5) as f64));
+        }
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_time_axis_labels_scale_with_width() {
+        assert_eq!(vec!["0", "60"], time_axis_labels(0.0, 60.0, 20));
+        assert_eq!(
+            vec!["0", "15", "30", "45", "60"],
+            time_axis_labels(0.0, 60.0, 80)
+        );
+    }
+
+    #[test]
+    fn test_draw_ui_log_mode_collapsed_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Logs);
+        app.logs.push("other".to_string());
+        app.logs.push("log_line".to_string());
+        app.collapse_duplicates = true;
+        app.vertical_scroll = 0;
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_log_mode_ansi_colors() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Logs);
+        app.logs = vec!["\x1b[31mred\x1b[0m plain".to_string()];
+        app.search_query.clear();
+        app.vertical_scroll = 0;
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+        let buffer = terminal.backend().buffer().clone();
+        assert_eq!("r", buffer[(9, 3)].symbol());
+        assert_eq!(Color::Reset, buffer[(9, 3)].fg);
+
+        app.ansi_colors = true;
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        assert_eq!("r", buffer[(9, 3)].symbol());
+        assert_eq!(Color::Red, buffer[(9, 3)].fg);
+        assert_eq!(Color::Reset, buffer[(13, 3)].fg);
+    }
+
+    #[test]
+    fn test_draw_ui_log_mode_exited_container_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Logs);
+        app.selected = 1;
+        app.logs = vec!["shutting down".to_string()];
+        app.vertical_scroll = 0;
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_log_mode_unread_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Logs);
+        app.logs = vec!["seen".to_string(), "new 1".to_string(), "new 2".to_string()];
+        app.unread_from = Some(1);
+        app.search_query.clear();
+        app.vertical_scroll = 0;
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        assert!(
+            terminal.backend().buffer()[(9, 3)]
+                .modifier
+                .contains(Modifier::UNDERLINED)
+        );
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_log_mode_structured_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Logs);
+        app.logs = vec![
+            r#"{"time":"10:00:00","level":"info","msg":"listening","port":80}"#.to_string(),
+            "plain line".to_string(),
+        ];
+        app.structured_logs = true;
+        app.vertical_scroll = 0;
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_sparkline() {
+        let mut history = MaxSlidingWindow::default();
+        for (i, value) in [0.0, 50.0, 100.0].into_iter().enumerate() {
+            history.add((i as f64, value));
+        }
+        assert_eq!("▁▅█", sparkline(&history, 10));
+        assert_eq!("▅█", sparkline(&history, 2));
+
+        history.add((3.0, 200.0));
+        assert_eq!("▁▃▅█", sparkline(&history, 10));
+    }
+
+    #[test]
+    fn test_draw_ui_cpu_sparkline_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Normal);
+        app.show_cpu = true;
+        let history = app.cpu_history.entry("id1".to_string()).or_default();
+        for (i, value) in [5.0, 20.0, 60.0, 90.0].into_iter().enumerate() {
+            history.add((i as f64, value));
+        }
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn cpu_axis_scales_past_one_core() {
+        assert_eq!(100.0, cpu_axis_max(None, Some(4)));
+        assert_eq!(20.0, cpu_axis_max(Some(17.3), Some(4)));
+        assert_eq!(200.0, cpu_axis_max(Some(130.0), None));
+        assert_eq!(500.0, cpu_axis_max(Some(230.0), Some(8)));
+        // Four cores top out at 400, short of the next round number.
+        assert_eq!(400.0, cpu_axis_max(Some(310.0), Some(4)));
+    }
+
+    #[test]
+    fn test_draw_ui_sorted_by_newest_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Normal);
+        app.config.sort = ContainerSort::Newest;
+        app.config.time_zone = TimeZone::Utc;
+        app.container_data[0].created = Some(1_700_000_000);
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_limit_context() {
+        assert_eq!(
+            "CPU: using 45% of 2-core limit",
+            cpu_limit_context(Some(90.0), Some(2.0), Some(8), CpuScale::Aggregate)
+        );
+        assert_eq!(
+            "CPU: 0.5-core limit",
+            cpu_limit_context(None, Some(0.5), None, CpuScale::Aggregate)
+        );
+        assert_eq!(
+            "CPU: no limit, of 400% max",
+            cpu_limit_context(Some(180.0), None, Some(4), CpuScale::Aggregate)
+        );
+        assert_eq!(
+            "CPU: no limit, normalized over 4 cores",
+            cpu_limit_context(Some(180.0), None, Some(4), CpuScale::Normalized)
+        );
+        assert_eq!(
+            "CPU: no limit",
+            cpu_limit_context(Some(12.0), None, None, CpuScale::Aggregate)
+        );
+        assert_eq!(
+            "Memory: using 30% of 512.0MiB limit",
+            memory_limit_context(Some(30.0), Some(512 * 1024 * 1024))
+        );
+        assert_eq!("Memory: no limit", memory_limit_context(None, None));
+    }
+
+    #[test]
+    fn test_status_color() {
+        let container = |state: &str, status: &str| ContainerInfo {
+            state: state.into(),
+            status: status.into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            Some(Color::Green),
+            status_color(&container("running", "Up 2 hours (healthy)"))
+        );
+        assert_eq!(
+            Some(Color::Red),
+            status_color(&container("running", "Up 2 hours (unhealthy)"))
+        );
+        assert_eq!(
+            Some(Color::Red),
+            status_color(&container("exited", "Exited (1) 5 minutes ago"))
+        );
+        assert_eq!(
+            Some(Color::Yellow),
+            status_color(&container("restarting", "Restarting (1) 3 seconds ago"))
+        );
+        assert_eq!(None, status_color(&container("", "")));
+    }
+
+    #[test]
+    fn test_ip_color() {
+        let container = |state: &str, ip: &str| ContainerInfo {
+            state: state.into(),
+            ip: ip.into(),
+            ..Default::default()
+        };
+        assert_eq!(None, ip_color(&container("running", "172.17.0.2")));
+        assert_eq!(Some(Color::Yellow), ip_color(&container("running", NO_IP)));
+        assert_eq!(Some(Color::DarkGray), ip_color(&container("exited", NO_IP)));
+    }
+
+    #[test]
+    fn test_fresh_entries() {
+        let entries = [("a", 1), ("b", 3), ("c", 1)];
+        assert_eq!(0, fresh_entries(&entries, 0));
+        assert_eq!(1, fresh_entries(&entries, 1));
+        assert_eq!(2, fresh_entries(&entries, 2));
+        assert_eq!(2, fresh_entries(&entries, 4));
+        assert_eq!(3, fresh_entries(&entries, 10));
+    }
+
+    #[test]
+    fn test_draw_ui_highlights_fresh_log_lines() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Logs);
+        app.logs = vec!["old".to_string(), "new".to_string()];
+        app.vertical_scroll = 0;
+        app.search_query.clear();
+        app.last_log_flush = Some((1, std::time::Instant::now()));
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        assert_eq!(Color::Reset, buffer[(9, 3)].fg);
+        assert_eq!(Color::Green, buffer[(9, 4)].fg);
+    }
+
+    #[test]
+    fn test_collapse_duplicates() {
+        let logs: Vec<String> = ["a", "a", "b", "a", "a", "a"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            vec![("a", 2), ("b", 1), ("a", 3)],
+            collapse_duplicates(&logs)
+        );
+    }
+
+    #[test]
+    fn test_draw_ui_help_mode_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let app = create_app_state_for_test(&AppMode::Help);
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_log_mode_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let app = create_app_state_for_test(&AppMode::Logs);
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_search_logs_mode_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Search);
+        app.last_mode = AppMode::Logs;
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_search_normal_mode_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Search);
+        app.last_mode = AppMode::Normal;
+        app.search_query = "img2".into();
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_memory_panel_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Normal);
+        app.show_memory = true;
+        app.container_data[0].memory = Some(MemoryUsage {
+            used: 256 * 1024 * 1024,
+            limit: 1024 * 1024 * 1024,
+        });
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_draw_ui_memory_panel_filtered_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Normal);
+        app.show_memory = true;
+        app.container_data[0].memory = Some(MemoryUsage {
+            used: 256 * 1024 * 1024,
+            limit: 1024 * 1024 * 1024,
+        });
+        app.container_data[1].memory = Some(MemoryUsage {
+            used: 768 * 1024 * 1024,
+            limit: 1024 * 1024 * 1024,
+        });
+        app.table_filter = Some("name2".into());
+        app.selected = 1;
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!("512.0B", format_bytes(512));
+        assert_eq!("1.5KiB", format_bytes(1536));
+        assert_eq!("2.0GiB", format_bytes(2 * 1024 * 1024 * 1024));
+    }
+
+    #[test]
+    fn highlight_matches_inverts_matched_chars() {
+        let line = highlight_matches("web", &[0, 2]);
+        let contents: Vec<&str> = line
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect();
+        assert_eq!(vec!["w", "e", "b"], contents);
+        assert!(
+            line.spans[0]
+                .style
+                .add_modifier
+                .contains(Modifier::REVERSED)
+        );
+        assert!(
+            !line.spans[1]
+                .style
+                .add_modifier
+                .contains(Modifier::REVERSED)
+        );
+    }
+
+    #[test]
+    fn test_search_prompt_places_cursor() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = create_app_state_for_test(&AppMode::Search);
+        app.search_query = "img2".into();
+        app.search_query.move_left();
+
+        terminal.draw(|f| draw_ui(f, &app)).unwrap();
+
+        let position = terminal.get_cursor_position().unwrap();
+        assert_eq!((5, 18), (position.x, position.y));
+    }
+
+    #[test]
+    fn test_scroll_position() {
+        assert_eq!("line 0/0", scroll_position(0, 10, 0));
+        assert_eq!("line 10/1000 (1%)", scroll_position(0, 10, 1000));
+        assert_eq!("line 340/1000 (34%)", scroll_position(330, 10, 1000));
+        assert_eq!("line 5/5 (100%)", scroll_position(3, 10, 5));
+    }
+
     #[test]
     fn test_centered_rect() {
         let area = Rect::new(0, 0, 100, 100);
@@ -0,0 +1,600 @@
+//! Drives the Docker-facing code against canned daemon responses.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bollard::container::{
+    InspectContainerOptions, ListContainersOptions, LogOutput, LogsOptions, Stats, StatsOptions,
+};
+use bollard::errors::Error;
+use bollard::models::{ContainerInspectResponse, ContainerSummary, ImageInspect};
+use chrono::DateTime;
+use futures::stream::{self, BoxStream, StreamExt};
+use serde_json::{Value, json};
+use tokio::sync::RwLock;
+use tugboat::app::{AppState, ParkedLogs, SharedState};
+use tugboat::diagnostics::Level;
+use tugboat::docker::{self, DockerClient};
+
+#[derive(Default)]
+struct FakeDocker {
+    containers: Vec<ContainerSummary>,
+    inspects: HashMap<String, ContainerInspectResponse>,
+    missing_images: Vec<String>,
+    stats: Vec<Stats>,
+    logs: Vec<&'static str>,
+    /// Fail listing and logs like a socket the user has no access to.
+    denied: bool,
+}
+
+fn permission_denied() -> Error {
+    let err = std::io::Error::from(std::io::ErrorKind::PermissionDenied);
+    Error::IOError { err }
+}
+
+fn not_found() -> Error {
+    Error::DockerResponseServerError {
+        status_code: 404,
+        message: "No such object".to_string(),
+    }
+}
+
+#[async_trait]
+impl DockerClient for FakeDocker {
+    async fn list_containers(
+        &self,
+        _options: Option<ListContainersOptions<String>>,
+    ) -> Result<Vec<ContainerSummary>, Error> {
+        if self.denied {
+            return Err(permission_denied());
+        }
+        Ok(self.containers.clone())
+    }
+
+    async fn inspect_container(
+        &self,
+        container_id: &str,
+        _options: Option<InspectContainerOptions>,
+    ) -> Result<ContainerInspectResponse, Error> {
+        self.inspects
+            .get(container_id)
+            .cloned()
+            .ok_or_else(not_found)
+    }
+
+    async fn inspect_image(&self, image_id: &str) -> Result<ImageInspect, Error> {
+        if self.missing_images.iter().any(|id| id == image_id) {
+            return Err(not_found());
+        }
+        Ok(ImageInspect::default())
+    }
+
+    fn stats(
+        &self,
+        _container_id: &str,
+        _options: Option<StatsOptions>,
+    ) -> BoxStream<'static, Result<Stats, Error>> {
+        stream::iter(self.stats.clone().into_iter().map(Ok)).boxed()
+    }
+
+    fn logs(
+        &self,
+        _container_id: &str,
+        _options: Option<LogsOptions<String>>,
+    ) -> BoxStream<'static, Result<LogOutput, Error>> {
+        if self.denied {
+            return stream::iter([Err(permission_denied())]).boxed();
+        }
+        let chunks = self.logs.iter().map(|line| {
+            Ok(LogOutput::StdOut {
+                message: line.as_bytes().to_vec().into(),
+            })
+        });
+        stream::iter(chunks.collect::<Vec<_>>()).boxed()
+    }
+}
+
+fn summary(id: &str, name: &str, image_id: &str, state: &str) -> ContainerSummary {
+    serde_json::from_value(json!({
+        "Id": id,
+        "Names": [name],
+        "Image": "web:latest",
+        "ImageID": image_id,
+        "Command": "nginx -g 'daemon off;'",
+        "State": state,
+        "Status": "Up 2 hours",
+        "Created": 1_700_000_000,
+        "Labels": {"com.example.team": "web"},
+    }))
+    .unwrap()
+}
+
+fn inspect(network_settings: Value) -> ContainerInspectResponse {
+    serde_json::from_value(json!({
+        "NetworkSettings": network_settings,
+        "HostConfig": {
+            "NanoCpus": 1_500_000_000,
+            "Memory": 268_435_456,
+            "RestartPolicy": {"Name": "on-failure", "MaximumRetryCount": 3},
+        },
+    }))
+    .unwrap()
+}
+
+/// Reclaimable page cache; `docker stats` leaves it out of the memory in use.
+const PAGE_CACHE: u64 = 50_000;
+
+fn cgroup_v2_memory_stats() -> Value {
+    let counters = "anon file kernel_stack slab sock shmem file_mapped file_dirty file_writeback \
+        anon_thp inactive_anon active_anon inactive_file active_file unevictable \
+        slab_reclaimable slab_unreclaimable pgfault pgmajfault workingset_refault \
+        workingset_activate workingset_nodereclaim pgrefill pgscan pgsteal pgactivate \
+        pgdeactivate pglazyfree pglazyfreed thp_fault_alloc thp_collapse_alloc";
+    let mut stats: serde_json::Map<String, Value> = counters
+        .split_whitespace()
+        .map(|name| (name.to_string(), json!(0)))
+        .collect();
+    stats.insert("inactive_file".to_string(), json!(PAGE_CACHE));
+    Value::Object(stats)
+}
+
+fn stats_sample(total_usage: u64, system_usage: u64, memory_usage: u64) -> Stats {
+    let cpu = |total: u64, system: u64| {
+        json!({
+            "cpu_usage": {"total_usage": total, "usage_in_usermode": 0, "usage_in_kernelmode": 0},
+            "system_cpu_usage": system,
+            "online_cpus": 2,
+            "throttling_data": {"periods": 0, "throttled_periods": 0, "throttled_time": 0},
+        })
+    };
+    serde_json::from_value(json!({
+        "read": "2025-01-01T00:00:01Z",
+        "preread": "2025-01-01T00:00:00Z",
+        "num_procs": 0,
+        "pids_stats": {},
+        "memory_stats": {
+            "usage": memory_usage + PAGE_CACHE,
+            "limit": 1_000_000,
+            "stats": cgroup_v2_memory_stats(),
+        },
+        "blkio_stats": {},
+        "cpu_stats": cpu(total_usage, system_usage),
+        "precpu_stats": cpu(0, 0),
+        "storage_stats": {},
+    }))
+    .unwrap()
+}
+
+fn shared(app: AppState) -> SharedState {
+    Arc::new(RwLock::new(app))
+}
+
+#[tokio::test]
+async fn builds_rows_from_list_and_inspect() {
+    let docker = FakeDocker {
+        containers: vec![
+            summary("0123456789abcdef0123", "/web", "sha256:web", "running"),
+            summary("abc", "/db", "sha256:web", "running"),
+            summary("fedcba9876543210", "/gone", "sha256:web", "exited"),
+        ],
+        inspects: HashMap::from([
+            (
+                "0123456789abcdef0123".to_string(),
+                inspect(json!({"IPAddress": "172.17.0.2"})),
+            ),
+            (
+                "abc".to_string(),
+                inspect(json!({
+                    "IPAddress": "",
+                    "Networks": {
+                        "frontend": {"IPAddress": "172.19.0.3"},
+                        "backend": {"IPAddress": "172.20.0.3"},
+                    },
+                })),
+            ),
+        ]),
+        ..Default::default()
+    };
+
+    let result = docker::fetch_container_data(&docker, None, false).await;
+    let mut app = AppState::default();
+    app.apply_refresh(result.map_err(|e| e.to_string()));
+
+    assert_eq!(None, app.refresh_error);
+    let rows: Vec<Vec<String>> = app.container_data.iter().map(|c| c.row()).collect();
+    assert_eq!(
+        vec!["abc", "web:latest", "Up 2 hours", "/db"],
+        rows[0][..4].to_vec()
+    );
+    assert_eq!("172.20.0.3, 172.19.0.3", rows[0][5]);
+    assert_eq!("fedcba987654", rows[1][0]);
+    assert_eq!("N/A?", rows[1][5]);
+    assert_eq!(Some(1_700_000_000), app.container_data[0].created);
+    assert_eq!("0123456789ab", rows[2][0]);
+    assert_eq!("172.17.0.2", rows[2][5]);
+    assert_eq!(
+        Some("on-failure (max 3 retries)"),
+        app.container_data[2].restart_policy.as_deref()
+    );
+    assert_eq!(
+        Some("web"),
+        app.container_data[2]
+            .labels
+            .get("com.example.team")
+            .map(String::as_str)
+    );
+}
+
+#[tokio::test]
+async fn explains_a_socket_without_access() {
+    let docker = FakeDocker {
+        denied: true,
+        ..Default::default()
+    };
+
+    let result = docker::fetch_container_data(&docker, None, false).await;
+    let mut app = AppState::default();
+    app.apply_refresh(result.map_err(|e| e.to_string()));
+
+    assert_eq!(
+        Some(docker::PERMISSION_DENIED),
+        app.refresh_error.as_deref()
+    );
+}
+
+#[tokio::test]
+async fn marks_containers_whose_image_is_gone() {
+    let docker = FakeDocker {
+        containers: vec![
+            summary("aaa", "/old", "sha256:old", "exited"),
+            summary("bbb", "/new", "sha256:new", "exited"),
+        ],
+        missing_images: vec!["sha256:old".to_string()],
+        ..Default::default()
+    };
+
+    let containers = docker::fetch_container_data(&docker, None, false)
+        .await
+        .unwrap();
+    assert_eq!("/new", containers[0].names);
+    assert!(!containers[0].image_missing);
+    assert_eq!("/old", containers[1].names);
+    assert_eq!("web:latest (missing)", containers[1].row()[1]);
+}
+
+#[tokio::test]
+async fn samples_usage_of_running_containers_only() {
+    let docker = FakeDocker {
+        containers: vec![
+            summary("aaa", "/api", "sha256:web", "running"),
+            summary("bbb", "/batch", "sha256:web", "exited"),
+        ],
+        stats: vec![stats_sample(200, 1000, 250_000)],
+        ..Default::default()
+    };
+
+    let containers = docker::fetch_container_data(&docker, None, true)
+        .await
+        .unwrap();
+    let memory = containers[0].memory.unwrap();
+    assert_eq!((250_000, 1_000_000), (memory.used, memory.limit));
+    assert!(containers[0].cpu_stats.is_some());
+    assert_eq!(None, containers[1].memory);
+    assert_eq!(None, containers[1].cpu_stats);
+}
+
+#[tokio::test]
+async fn stats_stream_fills_the_windows() {
+    let docker = FakeDocker {
+        inspects: HashMap::from([("aaa".to_string(), inspect(json!({})))]),
+        stats: vec![
+            stats_sample(100, 1000, 100_000),
+            stats_sample(200, 1000, 200_000),
+            stats_sample(300, 1000, 300_000),
+        ],
+        ..Default::default()
+    };
+    let app_state = shared(AppState::default());
+
+    docker::collect_stats(&docker, "aaa", &app_state).await;
+
+    let app = app_state.read().await;
+    let cpu: Vec<f64> = app.cpu_data.data.iter().map(|(_, cpu)| *cpu).collect();
+    let mem: Vec<f64> = app.mem_data.data.iter().map(|(_, mem)| *mem).collect();
+    assert_eq!(vec![20.0, 40.0, 60.0], cpu);
+    assert_eq!(vec![10.0, 20.0, 30.0], mem);
+    assert_eq!(Some(60.0), app.cpu_data.get_max());
+    assert_eq!(None, app.stats_message);
+    assert_eq!(Some(2), app.online_cpus);
+    let limits = app.resource_limits.unwrap();
+    assert_eq!(Some(1.5), limits.cpus);
+    assert_eq!(Some(268_435_456), limits.memory);
+}
+
+#[tokio::test]
+async fn stats_interval_averages_samples_into_points() {
+    let docker = FakeDocker {
+        stats: vec![
+            stats_sample(100, 1000, 100_000),
+            stats_sample(300, 1000, 300_000),
+            stats_sample(200, 1000, 200_000),
+            stats_sample(400, 1000, 400_000),
+            stats_sample(500, 1000, 500_000),
+        ],
+        ..Default::default()
+    };
+    let mut app = AppState::default();
+    app.config.stats_interval_secs = 2;
+    let app_state = shared(app);
+
+    docker::collect_stats(&docker, "aaa", &app_state).await;
+
+    let app = app_state.read().await;
+    let cpu: Vec<f64> = app.cpu_data.data.iter().map(|(_, cpu)| *cpu).collect();
+    let mem: Vec<f64> = app.mem_data.data.iter().map(|(_, mem)| *mem).collect();
+    assert_eq!(vec![40.0, 60.0], cpu);
+    assert_eq!(vec![20.0, 30.0], mem);
+}
+
+#[tokio::test]
+async fn empty_stats_stream_explains_why() {
+    let app_state = shared(AppState::default());
+
+    docker::collect_stats(&FakeDocker::default(), "aaa", &app_state).await;
+
+    let app = app_state.read().await;
+    assert!(app.cpu_data.data.is_empty());
+    assert_eq!(
+        Some("No stats available (container not running)"),
+        app.stats_message.as_deref()
+    );
+    let warning = app.diagnostics.entries().next().unwrap();
+    assert_eq!(Level::Warn, warning.level);
+    assert!(
+        warning
+            .message
+            .starts_with("Resource limits of aaa unknown")
+    );
+}
+
+#[tokio::test]
+async fn log_stream_replaces_the_loading_state() {
+    let docker = FakeDocker {
+        logs: vec!["starting\n", "listening on :80\n"],
+        ..Default::default()
+    };
+    let app_state = shared(AppState {
+        logs_loading: true,
+        ..Default::default()
+    });
+
+    docker::collect_logs(&docker, "aaa", false, None, &app_state).await;
+
+    let app = app_state.read().await;
+    assert!(!app.logs_loading);
+    assert_eq!(vec!["starting\n", "listening on :80\n"], app.logs);
+    assert_eq!(2, app.log_throughput.lines);
+}
+
+#[tokio::test]
+async fn log_filter_hides_noise_until_turned_off() {
+    let docker = FakeDocker {
+        logs: vec![
+            "GET /health 200\n",
+            "\x1b[31merror\x1b[0m: disk full\n",
+            "GET /health 200\n",
+        ],
+        ..Default::default()
+    };
+    let mut app = AppState {
+        logs_loading: true,
+        ..Default::default()
+    };
+    app.config.log_filter.exclude = vec!["/health".to_string()];
+    app.config.log_filter.include = vec!["error: ".to_string(), "GET".to_string()];
+    let app_state = shared(app);
+
+    docker::collect_logs(&docker, "aaa", false, None, &app_state).await;
+    {
+        let app = app_state.read().await;
+        assert_eq!(vec!["\x1b[31merror\x1b[0m: disk full\n"], app.logs);
+        assert_eq!(2, app.hidden_log_lines);
+        assert_eq!(3, app.log_throughput.lines);
+    }
+
+    {
+        let mut app = app_state.write().await;
+        app.log_filter_enabled = false;
+        app.logs_loading = true;
+    }
+    docker::collect_logs(&docker, "aaa", false, None, &app_state).await;
+    assert_eq!(3, app_state.read().await.logs.len());
+}
+
+#[tokio::test]
+async fn log_stream_flags_dropped_history() {
+    let docker = FakeDocker {
+        logs: vec!["line\n"; 1200],
+        ..Default::default()
+    };
+    let app_state = shared(AppState {
+        logs_loading: true,
+        ..Default::default()
+    });
+
+    docker::collect_logs(&docker, "aaa", false, None, &app_state).await;
+
+    let app = app_state.read().await;
+    assert_eq!(1000, app.logs.len());
+    assert!(app.logs_truncated);
+}
+
+#[tokio::test]
+async fn empty_log_stream_says_so() {
+    let app_state = shared(AppState {
+        logs_loading: true,
+        ..Default::default()
+    });
+
+    docker::collect_logs(&FakeDocker::default(), "aaa", false, None, &app_state).await;
+
+    let app = app_state.read().await;
+    assert!(!app.logs_loading);
+    assert_eq!(vec!["No logs"], app.logs);
+}
+
+#[tokio::test]
+async fn resumed_log_stream_continues_after_the_last_line_shown() {
+    let docker = FakeDocker {
+        logs: vec![
+            "2023-11-14T22:13:20.1Z first\n",
+            "2023-11-14T22:13:20.2Z second\n",
+            "2023-11-14T22:13:20.3Z logged while disconnected\n",
+        ],
+        ..Default::default()
+    };
+    let last_shown = DateTime::parse_from_rfc3339("2023-11-14T22:13:20.2Z")
+        .unwrap()
+        .to_utc();
+    let app_state = shared(AppState {
+        logs: vec!["first\n".to_string(), "second\n".to_string()],
+        last_log_time: Some(last_shown),
+        ..Default::default()
+    });
+
+    docker::collect_logs(&docker, "aaa", false, Some(last_shown), &app_state).await;
+
+    let app = app_state.read().await;
+    assert_eq!(
+        vec!["first\n", "second\n", "logged while disconnected\n"],
+        app.logs
+    );
+    assert!(app.last_log_time > Some(last_shown));
+}
+
+#[tokio::test]
+async fn export_writes_the_whole_history() {
+    let docker = FakeDocker {
+        logs: vec!["\x1b[32mline\x1b[0m\n"; 1500],
+        ..Default::default()
+    };
+    let file = tempfile::NamedTempFile::new().unwrap();
+
+    docker::export_logs(&docker, "aaa", file.path())
+        .await
+        .unwrap();
+
+    let exported = std::fs::read_to_string(file.path()).unwrap();
+    assert_eq!(1500, exported.lines().count());
+    assert!(exported.lines().all(|line| line == "line"));
+}
+
+#[tokio::test]
+async fn writes_logs_without_colors_unless_raw() {
+    let docker = FakeDocker {
+        logs: vec!["\x1b[32mready\x1b[0m\n", "GET /\n"],
+        ..Default::default()
+    };
+
+    let mut out = Vec::new();
+    docker::write_logs(&docker, "web", 2, false, &mut out)
+        .await
+        .unwrap();
+    assert_eq!("ready\nGET /\n", String::from_utf8(out).unwrap());
+
+    let mut raw = Vec::new();
+    docker::write_logs(&docker, "web", 2, true, &mut raw)
+        .await
+        .unwrap();
+    assert!(String::from_utf8(raw).unwrap().starts_with("\x1b[32mready"));
+}
+
+#[tokio::test]
+async fn printing_logs_explains_a_socket_without_access() {
+    let docker = FakeDocker {
+        denied: true,
+        ..Default::default()
+    };
+
+    let error = docker::write_logs(&docker, "web", 2, false, &mut Vec::new())
+        .await
+        .unwrap_err();
+    assert_eq!(docker::PERMISSION_DENIED, error.to_string());
+}
+
+#[tokio::test]
+async fn parked_log_stream_fills_its_own_buffer() {
+    let docker = FakeDocker {
+        logs: vec!["late line\n"],
+        ..Default::default()
+    };
+    let mut app = AppState {
+        logs: vec!["other container\n".to_string()],
+        logs_for: Some("bbb".to_string()),
+        ..Default::default()
+    };
+    app.parked_logs.push_back((
+        "aaa".to_string(),
+        ParkedLogs {
+            logs: vec!["early line\n".to_string()],
+            truncated: false,
+            last_log_time: None,
+            task: tokio::spawn(async {}),
+        },
+    ));
+    let app_state = shared(app);
+
+    docker::collect_logs(&docker, "aaa", true, None, &app_state).await;
+    let app = app_state.read().await;
+    assert_eq!(vec!["other container\n"], app.logs);
+    assert_eq!(
+        vec!["early line\n", "late line\n"],
+        app.parked_logs[0].1.logs
+    );
+    assert_eq!(0, app.log_throughput.lines);
+}
+
+#[tokio::test]
+async fn log_stream_parked_by_closing_the_overlay_keeps_its_lines() {
+    let docker = FakeDocker {
+        logs: vec!["late line\n"],
+        ..Default::default()
+    };
+    let mut app = AppState {
+        logs: vec!["early line\n".to_string()],
+        logs_for: Some("aaa".to_string()),
+        log_task: Some(tokio::spawn(async {})),
+        ..Default::default()
+    };
+    app.config.background_log_streams = 1;
+    // What Esc out of the logs overlay does.
+    app.park_log_stream();
+    assert_eq!(None, app.logs_for);
+    let app_state = shared(app);
+
+    docker::collect_logs(&docker, "aaa", true, None, &app_state).await;
+    let app = app_state.read().await;
+    assert!(app.logs.is_empty());
+    assert_eq!(0, app.vertical_scroll);
+    assert_eq!(
+        vec!["early line\n", "late line\n"],
+        app.parked_logs[0].1.logs
+    );
+    assert_eq!(0, app.log_throughput.lines);
+}
+
+#[tokio::test]
+async fn usage_snapshot_reads_one_sample() {
+    let docker = FakeDocker {
+        stats: vec![stats_sample(100, 1000, 200_000), stats_sample(500, 1000, 0)],
+        ..Default::default()
+    };
+
+    let usage = docker::snapshot_usage(&docker, "aaa").await;
+
+    assert_eq!(Some(20.0), usage.cpu);
+    assert_eq!(Some(20.0), usage.memory);
+    let idle = docker::snapshot_usage(&FakeDocker::default(), "aaa").await;
+    assert_eq!(docker::UsageSnapshot::default(), idle);
+}